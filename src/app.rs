@@ -1,17 +1,90 @@
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
 use ratatui::widgets::ListState;
 
-use crate::{list_tmux_sessions, list_tmuxifier_templates};
+use crate::error::AppError;
+use crate::fuzzy::{rank, Matched};
 use crate::input::EditorMode;
+use crate::sources::{sanitize_session_name, unique_session_name, Directory, Entry};
+use crate::state;
+use crate::templates::layout::expand_home;
+use crate::templates::snapshot::snapshot;
+use crate::templates::Template;
+use crate::tmux::{Pane, Session, TmuxBackend, TmuxError, Window};
+
+/// First entry of the template list: create a bare session.
+pub const NO_TEMPLATE: &str = "No Template";
 
 pub enum AppMode {
     MainMenu,
     CreateSession,
     ListSessions,
     SessionActionMenu(String),
+    /// Editing a new name for the session, typed into `input_buffer`.
+    RenameSession(String),
+    /// Windows of the session.
+    ListWindows(String),
+    /// Panes of a window: session name and window index.
+    ListPanes(String, u32),
+}
+
+/// Where the picker starts, from `Settings.default_view` or `--view`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum View {
+    Main,
+    Sessions,
+    Create,
+    /// The session list with only directories (projects, zoxide, ...).
+    Projects,
+}
+
+impl View {
+    pub const NAMES: [&'static str; 4] = ["main", "sessions", "create", "projects"];
+}
+
+impl FromStr for View {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "main" => Ok(View::Main),
+            "sessions" => Ok(View::Sessions),
+            "create" => Ok(View::Create),
+            "projects" => Ok(View::Projects),
+            _ => Err(format!("unknown view '{}' (expected one of: {})", s, View::NAMES.join(", "))),
+        }
+    }
+}
+
+/// The CreateSession field that receives typed text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CreateField {
+    Name,
+    Directory,
+}
+
+/// A way out when the typed session name can't be used.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NameFix {
+    /// Create the session under this name instead.
+    Rename(String),
+    /// Attach to this existing session instead of creating one.
+    Attach(String),
+}
+
+impl NameFix {
+    pub fn label(&self) -> String {
+        match self {
+            NameFix::Rename(name) => format!("use '{}'", name),
+            NameFix::Attach(name) => format!("attach to '{}'", name),
+        }
+    }
 }
 
 pub struct App {
-    pub name: String,
+    pub backend: Box<dyn TmuxBackend>,
     pub mode: AppMode,
     pub editor_mode: EditorMode, 
 
@@ -19,12 +92,26 @@ pub struct App {
     pub main_menu_selected: usize,
     pub main_list_state: ListState,
 
-    pub sessions: Vec<String>,
+    pub sessions: Vec<Session>,
     pub session_selected: usize,
     pub session_list_state: ListState,
     pub search_query: String,
+    /// Directories offered next to the running sessions (projects, ...).
+    pub directories: Vec<Directory>,
+    /// List only `directories`, not running sessions.
+    pub projects_only: bool,
+    /// Captured active pane of the highlighted session.
+    pub preview: String,
+
+    pub windows: Vec<Window>,
+    pub window_selected: usize,
+    pub window_list_state: ListState,
+
+    pub panes: Vec<Pane>,
+    pub pane_selected: usize,
+    pub pane_list_state: ListState,
 
-    pub templates: Vec<String>,
+    pub templates: Vec<Template>,
     pub template_selected: usize,
     pub template_list_state: ListState,
 
@@ -34,49 +121,642 @@ pub struct App {
 
 
     pub input_buffer: String,
+    /// Highlighted entry of `session_name_fixes` for `input_buffer`.
+    pub name_fix_selected: usize,
+
+    /// Starting directory typed in CreateSession; empty for tmux's default.
+    pub dir_buffer: String,
+    pub create_field: CreateField,
+    /// Highlighted completion of `dir_buffer`.
+    pub dir_selected: usize,
+    pub dir_list_state: ListState,
+
+    /// Quit once `attach` has switched the tmux client to a session.
+    pub exit_after_switch: bool,
+
+    /// `--popup`: compact session list that switches and quits on Enter.
+    pub popup_mode: bool,
+    /// `--print`: choosing entries records them in `picked` and quits.
+    pub print_mode: bool,
+    /// Entries toggled in Visual mode, by name.
+    pub marked: Vec<String>,
+    /// What `--print` writes to stdout; empty when cancelled.
+    pub picked: Vec<String>,
+
+    /// One-line feedback shown in the help bar until the next key press.
+    pub status: Option<String>,
+
+    /// Set when the TUI was suspended (e.g. attach) and must be repainted.
+    pub needs_clear: bool,
+
+    /// Failures and config problems not yet seen, oldest first. The first
+    /// one is shown as a popup until dismissed.
+    pub errors: Vec<AppError>,
 }
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new(backend: Box<dyn TmuxBackend>) -> Self {
+        let mut errors = Vec::new();
+        let sessions = backend.list_sessions().unwrap_or_else(|e| {
+            errors.push(AppError::Tmux(e));
+            vec![]
+        });
+        // Option to create without template
+        let mut templates = vec![Template::new("", NO_TEMPLATE)];
+        match backend.list_templates() {
+            Ok(t) => templates.extend(t),
+            Err(e) => errors.push(AppError::Tmux(e)),
+        }
         App {
-            name: "tmux-sessioniber".to_string(),
+            backend,
             mode: AppMode::MainMenu,
             editor_mode: EditorMode::Normal,
 
-            main_menu_items: vec!["Create Session", "List Sessions", "Quit"],
+            main_menu_items: vec!["Create Session", "List Sessions", "Save State", "Restore State", "Quit"],
             main_menu_selected: 0,
             main_list_state: ListState::default(),
 
-            sessions: list_tmux_sessions(),
+            sessions,
             session_selected: 0,
             session_list_state: ListState::default(),
             search_query: String::new(),
+            directories: Vec::new(),
+            projects_only: false,
+            preview: String::new(),
+
+            windows: Vec::new(),
+            window_selected: 0,
+            window_list_state: ListState::default(),
+
+            panes: Vec::new(),
+            pane_selected: 0,
+            pane_list_state: ListState::default(),
 
-            templates: list_tmuxifier_templates(),
+            templates,
             template_selected: 0,
             template_list_state: ListState::default(),
 
-            session_actions: vec![" Attach", " Delete", " Rename"], 
+            session_actions: vec![" Attach", " Delete", " Rename", " Windows", " Save as template"],
             session_action_selected: 0,
             session_action_list_state: ListState::default(),
 
             input_buffer: String::new(),
+            name_fix_selected: 0,
+
+            dir_buffer: String::new(),
+            create_field: CreateField::Name,
+            dir_selected: 0,
+            dir_list_state: ListState::default(),
+
+            exit_after_switch: true,
+            popup_mode: false,
+            print_mode: false,
+            marked: Vec::new(),
+            picked: Vec::new(),
+            status: None,
+            needs_clear: false,
+            errors,
         }
     }
 
-    pub fn filtered_sessions(&self) -> Vec<String> {
-        self.sessions
-            .iter()
-            .filter(|s| s.to_lowercase().contains(&self.search_query.to_lowercase()))
-            .cloned()
-            .collect()
+    /// Switch to the screen for `view`.
+    pub fn open_view(&mut self, view: View) {
+        self.projects_only = view == View::Projects;
+        self.mode = match view {
+            View::Main => AppMode::MainMenu,
+            View::Sessions | View::Projects => AppMode::ListSessions,
+            View::Create => AppMode::CreateSession,
+        };
+    }
+
+    /// Re-read the session list from the backend.
+    pub fn refresh_sessions(&mut self) {
+        let sessions = self.backend.list_sessions();
+        if let Some(sessions) = self.report(sessions) {
+            self.sessions = sessions;
+        }
+    }
+
+    /// Attach to (or switch the client to) `name`.
+    /// Returns true when the picker should exit afterwards.
+    pub fn attach(&mut self, name: &str) -> bool {
+        let result = self.backend.attach_session(name);
+        let switched = self.backend.switches_client();
+        if !switched {
+            self.needs_clear = true;
+        }
+        self.report(result).is_some() && switched && self.exit_after_switch
+    }
+
+    /// Attach to the session for `dir`, creating one named after it and
+    /// starting in it if needed. Returns true when the picker should exit afterwards.
+    pub fn open_directory(&mut self, dir: &Directory) -> bool {
+        if let Some(name) = self.session_for(dir).map(|s| s.name.clone()) {
+            return self.attach(&name);
+        }
+        // another folder of the same name may already have a session
+        let name = if self.sessions.iter().any(|s| s.name == dir.name) {
+            unique_session_name(&dir.path, |n| self.sessions.iter().any(|s| s.name == n))
+        } else {
+            dir.name.clone()
+        };
+        let result = self.backend.create_session(&name, None, Some(&dir.path));
+        if self.report(result).is_none() {
+            return false;
+        }
+        self.refresh_sessions();
+        self.attach(&name)
+    }
+
+    /// Capture the active pane of the highlighted session for the preview panel.
+    /// Failures end up in the panel itself rather than in the error popup.
+    pub fn refresh_preview(&mut self) {
+        self.preview = match self.selected_entry() {
+            Some(Entry::Session(session)) => self.backend.capture_pane(&session.name).unwrap_or_else(|e| e.to_string()),
+            Some(Entry::Directory(dir)) => format!("Enter to start session '{}' in {}", dir.name, dir.path),
+            None => String::new(),
+        };
+    }
+
+    /// Show the windows of `session`.
+    pub fn open_windows(&mut self, session: &str) {
+        let windows = self.backend.list_windows(session);
+        if let Some(windows) = self.report(windows) {
+            self.window_selected = windows.iter().position(|w| w.active).unwrap_or(0);
+            self.windows = windows;
+            self.mode = AppMode::ListWindows(session.to_string());
+        }
+    }
+
+    /// Show the panes of window `index` in `session`.
+    pub fn open_panes(&mut self, session: &str, index: u32) {
+        let panes = self.backend.list_panes(&format!("{}:{}", session, index));
+        if let Some(panes) = self.report(panes) {
+            self.pane_selected = panes.iter().position(|p| p.active).unwrap_or(0);
+            self.panes = panes;
+            self.mode = AppMode::ListPanes(session.to_string(), index);
+        }
+    }
+
+    /// Focus the window or pane `target` of `session`, then attach to it.
+    /// Returns true when the picker should exit afterwards.
+    pub fn jump_to(&mut self, session: &str, target: &str) -> bool {
+        let result = self.backend.select_target(target);
+        if self.report(result).is_none() {
+            return false;
+        }
+        self.attach(session)
+    }
+
+    /// Snapshot `session` into a new template of the same name.
+    pub fn save_as_template(&mut self, session: &str) {
+        let layout = snapshot(self.backend.as_ref(), session);
+        let Some(layout) = self.report(layout) else { return };
+        let saved = self.backend.save_template(session, &layout);
+        if let Some(template) = self.report(saved) {
+            self.status = Some(format!("Saved {} template '{}'", template.engine, template.name));
+            self.templates.push(template);
+        }
+    }
+
+    /// Write every running session to the state file.
+    pub fn save_state(&mut self) {
+        let saved = state::save(self.backend.as_ref(), &state::default_path());
+        if let Some(count) = self.report(saved) {
+            self.status = Some(format!("Saved {} sessions to {}", count, state::default_path().display()));
+        }
+    }
+
+    /// Rebuild the sessions from the state file that aren't running.
+    pub fn restore_state(&mut self) {
+        let restored = state::restore(self.backend.as_mut(), &state::default_path());
+        if let Some(report) = self.report(restored) {
+            self.status = Some(format!(
+                "Restored {} sessions, {} already running, {} failed",
+                report.restored.len(),
+                report.skipped.len(),
+                report.failed.len()
+            ));
+            if !report.failed.is_empty() {
+                self.errors.push(AppError::RestoreFailed(report.failed));
+            }
+        }
+        self.refresh_sessions();
+    }
+
+    /// Keep the session selection inside the (filtered) list after it shrinks.
+    pub fn clamp_session_selection(&mut self) {
+        let len = self.filtered_entries().len();
+        if self.session_selected >= len && len > 0 {
+            self.session_selected = len - 1;
+        }
+        self.session_list_state.select(Some(self.session_selected));
+    }
+
+    /// Unwrap a backend result, keeping the error for the TUI to show.
+    pub fn report<T>(&mut self, result: Result<T, TmuxError>) -> Option<T> {
+        match result {
+            Ok(v) => Some(v),
+            Err(e) => {
+                self.errors.push(AppError::Tmux(e));
+                None
+            }
+        }
+    }
+
+    /// Running sessions, then the directories that don't have a session yet.
+    /// A directory listed by several sources shows up once, and directories
+    /// whose name is already taken get one from `unique_session_name`. With
+    /// `projects_only`, every directory and no sessions.
+    pub fn entries(&self) -> Vec<Entry> {
+        let mut entries: Vec<Entry> = if self.projects_only {
+            Vec::new()
+        } else {
+            self.sessions.iter().cloned().map(Entry::Session).collect()
+        };
+        let mut seen: Vec<&str> = Vec::new();
+        let mut names: Vec<String> = self.sessions.iter().map(|s| s.name.clone()).collect();
+        for dir in &self.directories {
+            let session = self.session_for(dir);
+            if seen.contains(&dir.path.as_str()) || (session.is_some() && !self.projects_only) {
+                continue;
+            }
+            seen.push(&dir.path);
+            let mut dir = dir.clone();
+            if session.is_none() {
+                dir.name = unique_session_name(&dir.path, |n| names.iter().any(|t| t == n));
+                names.push(dir.name.clone());
+            }
+            entries.push(Entry::Directory(dir));
+        }
+        entries
+    }
+
+    /// The running session rooted in `dir`.
+    pub fn session_for(&self, dir: &Directory) -> Option<&Session> {
+        self.sessions.iter().find(|s| s.path == dir.path)
+    }
+
+    /// Entries fuzzy-matching `search_query`, best first, with match positions.
+    pub fn entry_matches(&self) -> Vec<Matched<Entry>> {
+        rank(&self.search_query, &self.entries(), |e| e.name())
+    }
+
+    pub fn template_matches(&self) -> Vec<Matched<Template>> {
+        rank(&self.search_query, &self.templates, |t| &t.name)
+    }
+
+    pub fn filtered_entries(&self) -> Vec<Entry> {
+        self.entry_matches().into_iter().map(|m| m.item).collect()
+    }
+
+    /// The highlighted row of the session list.
+    pub fn selected_entry(&self) -> Option<Entry> {
+        self.filtered_entries().into_iter().nth(self.session_selected)
+    }
+
+    pub fn filtered_templates(&self) -> Vec<Template> {
+        self.template_matches().into_iter().map(|m| m.item).collect()
     }
 
-    pub fn filtered_templates(&self) -> Vec<String> {
-        self.templates
-            .iter()
-            .filter(|s| s.to_lowercase().contains(&self.search_query.to_lowercase()))
-            .cloned()
-            .collect()
+    /// Subdirectories that `dir_buffer` could be completed to.
+    pub fn dir_completions(&self) -> Vec<String> {
+        complete_dir(&self.dir_buffer)
+    }
+
+    /// Replace `dir_buffer` with the highlighted completion.
+    pub fn complete_dir_buffer(&mut self) {
+        if let Some(dir) = self.dir_completions().into_iter().nth(self.dir_selected) {
+            self.dir_buffer = dir;
+            self.dir_selected = 0;
+        }
+    }
+
+    /// The directory a new session should start in, if one was typed.
+    pub fn start_dir(&self) -> Option<String> {
+        let dir = self.dir_buffer.trim();
+        if dir.is_empty() {
+            return None;
+        }
+        let dir = expand_home(dir);
+        match dir.trim_end_matches('/') {
+            "" => Some("/".to_string()),
+            trimmed => Some(trimmed.to_string()),
+        }
+    }
+
+    /// The session name typed in CreateSession.
+    pub fn new_session_name(&self) -> &str {
+        self.input_buffer.trim()
+    }
+
+    /// Apply the highlighted fix for the typed session name.
+    /// Returns true when the picker should exit afterwards.
+    pub fn apply_name_fix(&mut self) -> bool {
+        let fixes = session_name_fixes(self.new_session_name(), &self.sessions);
+        match fixes.into_iter().nth(self.name_fix_selected) {
+            Some(NameFix::Rename(name)) => {
+                self.input_buffer = name;
+                self.name_fix_selected = 0;
+            }
+            Some(NameFix::Attach(name)) => {
+                self.reset_create_form();
+                self.editor_mode = EditorMode::Normal;
+                self.mode = AppMode::MainMenu;
+                if self.attach(&name) {
+                    return true;
+                }
+                self.refresh_sessions();
+            }
+            None => {}
+        }
+        false
+    }
+
+    /// Mark the highlighted entry, or unmark it if it already is.
+    pub fn toggle_mark(&mut self) {
+        let Some(entry) = self.selected_entry() else { return };
+        let name = entry.name().to_string();
+        match self.marked.iter().position(|m| *m == name) {
+            Some(i) => {
+                self.marked.remove(i);
+            }
+            None => self.marked.push(name),
+        }
+    }
+
+    /// Record the marked entries, or the highlighted one if none are, as the
+    /// `--print` result.
+    pub fn pick(&mut self) {
+        self.picked = if self.marked.is_empty() {
+            self.selected_entry().map(|e| e.name().to_string()).into_iter().collect()
+        } else {
+            self.marked.clone()
+        };
+    }
+
+    /// Clear the CreateSession fields for the next session.
+    pub fn reset_create_form(&mut self) {
+        self.input_buffer.clear();
+        self.name_fix_selected = 0;
+        self.dir_buffer.clear();
+        self.dir_selected = 0;
+        self.create_field = CreateField::Name;
+    }
+}
+
+/// Ways to turn `name` into a session that can be created or attached:
+/// replacing the characters tmux rejects and, when the result is taken, a
+/// numeric suffix or the existing session.
+pub fn session_name_fixes(name: &str, sessions: &[Session]) -> Vec<NameFix> {
+    if name.trim().is_empty() {
+        return vec![];
+    }
+    let taken = |n: &str| sessions.iter().any(|s| s.name == n);
+    let clean = sanitize_session_name(name);
+    if !taken(&clean) {
+        return if clean == name { vec![] } else { vec![NameFix::Rename(clean)] };
+    }
+    let free = (2..)
+        .map(|i| format!("{}-{}", clean, i))
+        .find(|n| !taken(n))
+        .unwrap_or_default();
+    vec![NameFix::Rename(free), NameFix::Attach(clean)]
+}
+
+/// Why `dir` can't be used as a starting directory, if anything.
+pub fn start_dir_problem(dir: Option<&str>) -> Option<String> {
+    let dir = dir?;
+    (!Path::new(dir).is_dir()).then(|| format!("'{}' is not a directory", dir))
+}
+
+/// Subdirectories completing the partially typed path `input`, spelled the
+/// way it was typed (`~/co` completes to `~/code/`). Hidden directories are
+/// only offered once a `.` has been typed.
+pub fn complete_dir(input: &str) -> Vec<String> {
+    if input == "~" {
+        return vec!["~/".to_string()];
+    }
+    let (typed_parent, prefix) = match input.rfind('/') {
+        Some(i) => input.split_at(i + 1),
+        None => ("", input),
+    };
+    let parent = if typed_parent.is_empty() { ".".to_string() } else { expand_home(typed_parent) };
+    let Ok(entries) = fs::read_dir(&parent) else {
+        return vec![];
+    };
+
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter(|name| name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.')))
+        .collect();
+    names.sort();
+    names.into_iter().map(|name| format!("{}{}/", typed_parent, name)).collect()
+}
+
+/// Why `name` can't be used for a new session, if anything.
+pub fn session_name_problem(name: &str, sessions: &[Session]) -> Option<String> {
+    if name.trim().is_empty() {
+        return Some("name cannot be empty".to_string());
+    }
+    if let Some(c) = name.chars().find(|c| matches!(c, '.' | ':')) {
+        return Some(format!("'{}' is not allowed in session names", c));
+    }
+    if sessions.iter().any(|s| s.name == name) {
+        return Some(format!("session '{}' already exists", name));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::sources::DirectoryKind;
+    use crate::tmux::fake::{Call, FakeBackend};
+
+    /// An app with the session `api` running in `/work/api`.
+    fn app_with_api_session(directories: &[&str]) -> (App, Rc<RefCell<Vec<Call>>>) {
+        let mut fake = FakeBackend::new().with_sessions(&["api"]);
+        fake.sessions[0].path = "/work/api".to_string();
+        let calls = fake.calls();
+        let mut app = App::new(Box::new(fake));
+        app.directories = directories.iter().map(|d| Directory::new(d, DirectoryKind::Project)).collect();
+        (app, calls)
+    }
+
+    fn entry_names(app: &App) -> Vec<String> {
+        app.entries().iter().map(|e| e.name().to_string()).collect()
+    }
+
+    #[test]
+    fn directories_match_sessions_by_path() {
+        let (app, _) = app_with_api_session(&["/work/api", "/oss/api"]);
+        assert_eq!(entry_names(&app), ["api", "oss_api"]);
+    }
+
+    #[test]
+    fn clashing_directory_names_stay_unique() {
+        let (app, _) = app_with_api_session(&["/oss/api", "/tmp/oss/api", "/srv/web", "/opt/web"]);
+        assert_eq!(entry_names(&app), ["api", "oss_api", "oss_api-2", "web", "opt_web"]);
+    }
+
+    #[test]
+    fn opening_a_clashing_directory_creates_its_own_session() {
+        let (mut app, calls) = app_with_api_session(&["/oss/api"]);
+        let Some(Entry::Directory(dir)) = app.entries().pop() else { panic!("no directory entry") };
+
+        app.open_directory(&dir);
+        assert_eq!(
+            *calls.borrow(),
+            vec![
+                Call::Create { name: "oss_api".to_string(), template: None, dir: Some("/oss/api".to_string()) },
+                Call::Attach("oss_api".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn opening_a_directory_with_a_session_attaches() {
+        let (mut app, calls) = app_with_api_session(&[]);
+        app.open_directory(&Directory::new("/work/api", DirectoryKind::Project));
+        assert_eq!(*calls.borrow(), vec![Call::Attach("api".to_string())]);
+    }
+
+    #[test]
+    fn views_parse_from_their_names() {
+        let views: Vec<View> = View::NAMES.iter().map(|name| name.parse().unwrap()).collect();
+        assert_eq!(views, [View::Main, View::Sessions, View::Create, View::Projects]);
+        assert_eq!(
+            "list".parse::<View>(),
+            Err("unknown view 'list' (expected one of: main, sessions, create, projects)".to_string())
+        );
+        assert!("Main".parse::<View>().is_err());
+    }
+
+    #[test]
+    fn views_open_their_screen() {
+        let (mut app, _) = app_with_api_session(&[]);
+
+        app.open_view(View::Main);
+        assert!(matches!((&app.mode, app.projects_only), (AppMode::MainMenu, false)));
+        app.open_view(View::Create);
+        assert!(matches!((&app.mode, app.projects_only), (AppMode::CreateSession, false)));
+        app.open_view(View::Projects);
+        assert!(matches!((&app.mode, app.projects_only), (AppMode::ListSessions, true)));
+        app.open_view(View::Sessions);
+        assert!(matches!((&app.mode, app.projects_only), (AppMode::ListSessions, false)));
+    }
+
+    #[test]
+    fn projects_view_lists_only_directories() {
+        let (mut app, _) = app_with_api_session(&["/work/api", "/srv/web"]);
+        app.open_view(View::Projects);
+        assert_eq!(entry_names(&app), ["api", "web"]);
+        assert!(app.entries().iter().all(|e| matches!(e, Entry::Directory(_))));
+    }
+
+    fn rename(name: &str) -> NameFix {
+        NameFix::Rename(name.to_string())
+    }
+
+    #[test]
+    fn rejected_characters_are_replaced() {
+        let sessions = [Session::new("api")];
+        assert_eq!(session_name_fixes("a:b", &sessions), [rename("a_b")]);
+        assert_eq!(session_name_fixes("my.project", &sessions), [rename("my_project")]);
+        assert_eq!(session_name_fixes("web", &sessions), []);
+    }
+
+    #[test]
+    fn taken_names_get_a_suffix_or_attach() {
+        let sessions = [Session::new("api"), Session::new("api-2"), Session::new("a_b")];
+        assert_eq!(session_name_fixes("api", &sessions), [rename("api-3"), NameFix::Attach("api".to_string())]);
+        assert_eq!(session_name_fixes("a:b", &sessions), [rename("a_b-2"), NameFix::Attach("a_b".to_string())]);
+    }
+
+    #[test]
+    fn blank_names_have_no_fixes() {
+        assert_eq!(session_name_fixes("", &[]), []);
+        assert_eq!(session_name_fixes("  ", &[]), []);
+    }
+
+    #[test]
+    fn session_name_problems() {
+        let sessions = [Session::new("api")];
+        let problem = |name| session_name_problem(name, &sessions);
+        assert_eq!(problem(""), Some("name cannot be empty".to_string()));
+        assert_eq!(problem(" \t"), Some("name cannot be empty".to_string()));
+        assert_eq!(problem("a:b"), Some("':' is not allowed in session names".to_string()));
+        assert_eq!(problem("my.project"), Some("'.' is not allowed in session names".to_string()));
+        assert_eq!(problem("api"), Some("session 'api' already exists".to_string()));
+        assert_eq!(problem("web"), None);
+    }
+
+    /// A fresh directory under the temp dir holding `code/`, `config/`,
+    /// `.hidden/` and the file `cobalt.txt`.
+    fn dir_tree(test: &str) -> String {
+        let root = std::env::temp_dir().join(format!("sessioniber-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for dir in ["code", "config", ".hidden"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(root.join("cobalt.txt"), "").unwrap();
+        root.display().to_string()
+    }
+
+    #[test]
+    fn completes_subdirectories_as_typed() {
+        let root = dir_tree("complete");
+        let code_and_config = [format!("{}/code/", root), format!("{}/config/", root)];
+
+        assert_eq!(complete_dir(&format!("{}/co", root)), code_and_config);
+        assert_eq!(complete_dir(&format!("{}/", root)), code_and_config);
+        assert_eq!(complete_dir(&format!("{}/.", root)), [format!("{}/.hidden/", root)]);
+        assert!(complete_dir(&format!("{}/nope/", root)).is_empty());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn completes_home_with_a_tilde() {
+        let home = dirs::home_dir().unwrap().display().to_string();
+        let expected: Vec<String> =
+            complete_dir(&format!("{}/", home)).iter().map(|d| d.replacen(&home, "~", 1)).collect();
+
+        assert_eq!(complete_dir("~"), ["~/"]);
+        assert_eq!(complete_dir("~/"), expected);
+    }
+
+    #[test]
+    fn start_dir_expands_home_and_drops_trailing_slashes() {
+        let (mut app, _) = app_with_api_session(&[]);
+        let home = dirs::home_dir().unwrap().display().to_string();
+        let mut start_dir = |typed: &str| {
+            app.dir_buffer = typed.to_string();
+            app.start_dir()
+        };
+
+        assert_eq!(start_dir(""), None);
+        assert_eq!(start_dir("  "), None);
+        assert_eq!(start_dir("~"), Some(home.clone()));
+        assert_eq!(start_dir("~/code/"), Some(format!("{}/code", home)));
+        assert_eq!(start_dir("/srv/web//"), Some("/srv/web".to_string()));
+        assert_eq!(start_dir("/"), Some("/".to_string()));
+    }
+
+    #[test]
+    fn start_dir_must_be_a_directory() {
+        let root = dir_tree("start");
+
+        assert_eq!(start_dir_problem(None), None);
+        assert_eq!(start_dir_problem(Some(&format!("{}/.hidden", root))), None);
+        let file = format!("{}/cobalt.txt", root);
+        assert_eq!(start_dir_problem(Some(&file)), Some(format!("'{}' is not a directory", file)));
+        fs::remove_dir_all(&root).unwrap();
+        assert!(start_dir_problem(Some(&root)).is_some());
     }
 }
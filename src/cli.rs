@@ -0,0 +1,543 @@
+//! Command-line interface. Without a subcommand (or with `tui`) the picker
+//! starts; every other subcommand runs against tmux directly and reports
+//! failures on stderr.
+//!
+//! The `tsv` and `json` output of `ls` and `templates` is a stable schema,
+//! documented in their `--help`.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+
+use crate::app::{View, session_name_problem, start_dir_problem};
+use crate::config::{Config, ConfigProblem, LoadedConfig, Origin};
+use crate::error::{AppError, Result};
+use crate::resurrect;
+use crate::state;
+use crate::templates;
+use crate::templates::layout::expand_home;
+use crate::tmux::{Session, TmuxBackend};
+
+#[derive(Parser)]
+#[command(version, about = "tmux session manager")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Pick from the session list and print the choice to stdout instead of
+    /// attaching. Exits with 130 when cancelled.
+    #[arg(long)]
+    pub print: bool,
+    /// Compact session list for `tmux display-popup -E`: Enter switches the
+    /// client and exits.
+    #[arg(long)]
+    pub popup: bool,
+    /// Screen to start on: main, sessions, create or projects.
+    /// Overrides `default_view` in the config.
+    #[arg(long, value_parser = View::NAMES)]
+    pub view: Option<String>,
+    /// Override a config value, e.g. `--set settings.zoxide=false`.
+    /// Wins over every config file and environment variable.
+    #[arg(long = "set", value_name = "KEY=VALUE", global = true)]
+    pub set: Vec<String>,
+}
+
+impl Cli {
+    /// The config values set on the command line, as `key=value`.
+    pub fn overrides(&self) -> Vec<String> {
+        let mut overrides = self.set.clone();
+        if let Some(view) = &self.view {
+            overrides.push(format!("settings.default_view={}", view));
+        }
+        overrides
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// List running sessions
+    ///
+    /// `--format plain` prints one name per line. `--format tsv` prints one
+    /// session per line and `--format json` an array of objects, with these
+    /// fields in this order:
+    ///
+    ///   name      session name
+    ///   windows   number of windows
+    ///   attached  number of attached clients
+    ///   activity  Unix timestamp of the last activity
+    ///   path      session working directory
+    ///
+    /// New fields are only ever appended; existing ones keep their name and type.
+    #[command(verbatim_doc_comment)]
+    Ls {
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+    /// List the templates of the configured engines
+    ///
+    /// `--format plain` prints one name per line. `--format tsv` prints one
+    /// template per line and `--format json` an array of objects, with these
+    /// fields in this order:
+    ///
+    ///   name      template name, as passed to `new --template`
+    ///   engine    native, tmuxifier, tmuxinator or tmuxp
+    ///
+    /// New fields are only ever appended; existing ones keep their name and type.
+    #[command(verbatim_doc_comment)]
+    Templates {
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+    /// Create a detached session
+    New {
+        name: String,
+        /// Build the session from this template
+        #[arg(short, long)]
+        template: Option<String>,
+        /// Start the session in this directory
+        #[arg(short, long)]
+        dir: Option<String>,
+    },
+    /// Attach to a session (switches the client when run inside tmux)
+    Attach { name: String },
+    /// Switch the current tmux client to a session
+    Switch { name: String },
+    /// Kill a session
+    Kill { name: String },
+    /// Rename a session
+    Rename { old: String, new: String },
+    /// Start the interactive picker (the default)
+    Tui,
+    /// Inspect the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Print a tmux.conf `bind-key` line that opens the picker in a popup
+    PopupBinding {
+        /// Key pressed after the prefix
+        #[arg(long, default_value = "S")]
+        key: String,
+        #[arg(long, default_value = "60%")]
+        width: String,
+        #[arg(long, default_value = "60%")]
+        height: String,
+    },
+    /// Save every session to a state file
+    Save { file: Option<PathBuf> },
+    /// Recreate the sessions of a state file that aren't running
+    Restore { file: Option<PathBuf> },
+    /// Recreate the sessions of a tmux-resurrect save file
+    ImportResurrect {
+        file: Option<PathBuf>,
+        /// Only print what would be created
+        #[arg(long)]
+        preview: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Write a config file with the documented defaults
+    Init {
+        /// Overwrite an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print where the user config file is read from
+    Path,
+    /// Print every effective value
+    Show {
+        /// Also print where each value came from
+        #[arg(long)]
+        origin: bool,
+    },
+    /// Report problems in the config file with their line and column
+    Validate,
+    /// Print the documented defaults as TOML
+    DumpDefaults,
+}
+
+/// Output format of `ls` and `templates`.
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum Format {
+    /// Names only, one per line
+    #[default]
+    Plain,
+    /// Tab-separated fields, one record per line
+    Tsv,
+    /// A JSON array of objects
+    Json,
+}
+
+/// A session as printed by `ls`.
+#[derive(Serialize)]
+struct SessionRecord<'a> {
+    name: &'a str,
+    windows: usize,
+    attached: usize,
+    activity: u64,
+    path: &'a str,
+}
+
+/// A template as printed by `templates`.
+#[derive(Serialize)]
+struct TemplateRecord<'a> {
+    name: &'a str,
+    engine: &'a str,
+}
+
+/// Run a non-interactive subcommand, printing its output to `out`.
+pub fn run(command: Command, backend: &mut dyn TmuxBackend, out: &mut dyn Write) -> Result<()> {
+    match command {
+        Command::Ls { format } => {
+            let sessions = backend.list_sessions()?;
+            let records: Vec<SessionRecord> = sessions
+                .iter()
+                .map(|s| SessionRecord {
+                    name: &s.name,
+                    windows: s.windows,
+                    attached: s.attached,
+                    activity: s.activity,
+                    path: &s.path,
+                })
+                .collect();
+            print_records(
+                out,
+                format,
+                &records,
+                |r| r.name,
+                |r| {
+                    format!(
+                        "{}\t{}\t{}\t{}\t{}",
+                        r.name, r.windows, r.attached, r.activity, r.path
+                    )
+                },
+            )?;
+        }
+        Command::Templates { format } => {
+            let templates = backend.list_templates()?;
+            let records: Vec<TemplateRecord> = templates
+                .iter()
+                .map(|t| TemplateRecord {
+                    name: &t.name,
+                    engine: &t.engine,
+                })
+                .collect();
+            print_records(
+                out,
+                format,
+                &records,
+                |r| r.name,
+                |r| format!("{}\t{}", r.name, r.engine),
+            )?;
+        }
+        Command::New {
+            name,
+            template,
+            dir,
+        } => {
+            if let Some(problem) = session_name_problem(&name, &backend.list_sessions()?) {
+                return Err(AppError::Invalid(problem));
+            }
+            let dir = dir.map(|d| expand_home(&d));
+            if let Some(problem) = start_dir_problem(dir.as_deref()) {
+                return Err(AppError::Invalid(problem));
+            }
+            let template = match template {
+                Some(wanted) => Some(
+                    backend
+                        .list_templates()?
+                        .into_iter()
+                        .find(|t| t.name == wanted)
+                        .ok_or_else(|| {
+                            AppError::Invalid(format!("no template named '{}'", wanted))
+                        })?,
+                ),
+                None => None,
+            };
+            backend.create_session(&name, template.as_ref(), dir.as_deref())?;
+        }
+        Command::Attach { name } => {
+            require_session(backend, &name)?;
+            backend.attach_session(&name)?;
+        }
+        Command::Switch { name } => {
+            if !backend.switches_client() {
+                return Err(AppError::Invalid(
+                    "switch only works inside tmux; use attach".to_string(),
+                ));
+            }
+            require_session(backend, &name)?;
+            backend.attach_session(&name)?;
+        }
+        Command::Kill { name } => {
+            require_session(backend, &name)?;
+            backend.delete_session(&name)?;
+        }
+        Command::Rename { old, new } => {
+            let sessions = require_session(backend, &old)?;
+            if let Some(problem) = session_name_problem(&new, &sessions) {
+                return Err(AppError::Invalid(problem));
+            }
+            backend.rename_session(&old, &new)?;
+        }
+        Command::Config { .. } => unreachable!("config commands are run by main"),
+        Command::PopupBinding { key, width, height } => {
+            writeln!(out, "# tmux-sessioniber in a popup, on prefix + {}", key)?;
+            writeln!(
+                out,
+                "bind-key {} display-popup -E -w {} -h {} \"tmux-sessioniber --popup\"",
+                key, width, height
+            )?;
+        }
+        Command::Save { file } => {
+            let path = file.unwrap_or_else(state::default_path);
+            let count = state::save(backend, &path)?;
+            writeln!(out, "saved {} sessions to {}", count, path.display())?;
+        }
+        Command::Restore { file } => {
+            let path = file.unwrap_or_else(state::default_path);
+            let report = state::restore(backend, &path)?;
+            print_report(out, report, "restored", &BTreeMap::new())?;
+        }
+        Command::ImportResurrect { file, preview } => {
+            let path = file.unwrap_or_else(resurrect::default_path);
+            let sessions = resurrect::load(&path)?;
+            let commands = sessions
+                .iter()
+                .map(|s| {
+                    (
+                        s.name.clone(),
+                        s.layout
+                            .commands()
+                            .into_iter()
+                            .map(str::to_string)
+                            .collect(),
+                    )
+                })
+                .collect();
+            let report = state::restore_sessions(backend, sessions, preview)?;
+            print_report(
+                out,
+                report,
+                if preview { "would create" } else { "created" },
+                &commands,
+            )?;
+        }
+        Command::Tui => unreachable!("the TUI is started by main"),
+    }
+    Ok(())
+}
+
+/// Run a `config` subcommand on the `loaded` config. These don't need
+/// tmux, and work with a broken config.
+pub fn run_config(command: ConfigCommand, loaded: &LoadedConfig) -> Result<()> {
+    let path = Config::path();
+    match command {
+        ConfigCommand::Init { force } => {
+            if path.exists() && !force {
+                return Err(AppError::Invalid(format!(
+                    "{} already exists; use --force to overwrite it",
+                    path.display()
+                )));
+            }
+            Config::default().save()?;
+            println!("wrote {}", path.display());
+        }
+        ConfigCommand::Path => println!("{}", path.display()),
+        ConfigCommand::DumpDefaults => print!("{}", Config::default().to_documented_toml()),
+        ConfigCommand::Show { origin } => {
+            for (key, value, from) in loaded.values() {
+                if origin {
+                    println!("{} = {}  # {}", key, value, from);
+                } else {
+                    println!("{} = {}", key, value);
+                }
+            }
+        }
+        ConfigCommand::Validate => {
+            let config = &loaded.config;
+            let origin_of = |key: &str| loaded.origins.get(key).cloned().unwrap_or(Origin::Default);
+            // values of the right type that still can't be used
+            let mut unusable = Vec::new();
+            if let Err(e) = config.settings.default_view.parse::<View>() {
+                unusable.push(ConfigProblem::new(
+                    origin_of("settings.default_view"),
+                    format!("`settings.default_view`: {}", e),
+                ));
+            }
+            if let Err(e) =
+                templates::from_setting(&config.settings.template_engine, &Config::layouts_dir())
+            {
+                unusable.push(ConfigProblem::new(
+                    origin_of("settings.template_engine"),
+                    format!("`settings.template_engine`: {}", e),
+                ));
+            }
+            let problems: Vec<&ConfigProblem> = loaded.problems.iter().chain(&unusable).collect();
+            for problem in &problems {
+                eprintln!("{}", problem);
+            }
+            if !problems.is_empty() {
+                return Err(AppError::Invalid(format!(
+                    "{} problem(s) in the config",
+                    problems.len()
+                )));
+            }
+            println!("config ok");
+        }
+    }
+    Ok(())
+}
+
+/// Print `records` in `format`; `name` gives the plain output, `tsv` one line.
+fn print_records<T: Serialize>(
+    out: &mut dyn Write,
+    format: Format,
+    records: &[T],
+    name: impl Fn(&T) -> &str,
+    tsv: impl Fn(&T) -> String,
+) -> Result<()> {
+    match format {
+        Format::Plain => records
+            .iter()
+            .try_for_each(|r| writeln!(out, "{}", name(r)))?,
+        Format::Tsv => records
+            .iter()
+            .try_for_each(|r| writeln!(out, "{}", tsv(r)))?,
+        Format::Json => writeln!(
+            out,
+            "{}",
+            serde_json::to_string(records).map_err(io::Error::from)?
+        )?,
+    }
+    Ok(())
+}
+
+/// Print what a restore did, one session per line, with `verb` for the
+/// restored ones followed by the `commands` run in their panes. Fails when
+/// any session couldn't be restored.
+fn print_report(
+    out: &mut dyn Write,
+    report: state::RestoreReport,
+    verb: &str,
+    commands: &BTreeMap<String, Vec<String>>,
+) -> Result<()> {
+    for name in &report.restored {
+        writeln!(out, "{} {}", verb, name)?;
+        for command in commands.get(name).into_iter().flatten() {
+            writeln!(out, "  runs: {}", command)?;
+        }
+    }
+    for name in &report.skipped {
+        writeln!(out, "skipped {} (already running)", name)?;
+    }
+    if !report.failed.is_empty() {
+        return Err(AppError::RestoreFailed(report.failed));
+    }
+    Ok(())
+}
+
+/// The running sessions, or an error when `name` isn't one of them.
+fn require_session(backend: &dyn TmuxBackend, name: &str) -> Result<Vec<Session>> {
+    let sessions = backend.list_sessions()?;
+    if sessions.iter().any(|s| s.name == name) {
+        Ok(sessions)
+    } else {
+        Err(AppError::Invalid(format!("no session named '{}'", name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+    use crate::tmux::fake::FakeBackend;
+
+    /// Run `args` (after the binary name) against `backend` and return the output.
+    fn run_args(backend: &mut FakeBackend, args: &[&str]) -> Result<String> {
+        let cli =
+            Cli::try_parse_from(std::iter::once("tmux-sessioniber").chain(args.iter().copied()))
+                .unwrap();
+        let mut out = Vec::new();
+        run(cli.command.unwrap(), backend, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    fn invalid(result: Result<String>) -> String {
+        match result {
+            Err(AppError::Invalid(message)) => message,
+            other => panic!("expected an invalid request, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn ls_json_fields_keep_their_order() {
+        let mut fake = FakeBackend::new().with_sessions(&["api"]);
+        fake.sessions[0].attached = 1;
+        fake.sessions[0].activity = 1700000000;
+        fake.sessions[0].path = "/work/api".to_string();
+
+        let output = run_args(&mut fake, &["ls", "--format", "json"]).unwrap();
+        assert_eq!(
+            output,
+            "[{\"name\":\"api\",\"windows\":1,\"attached\":1,\"activity\":1700000000,\"path\":\"/work/api\"}]\n"
+        );
+        assert_eq!(
+            run_args(&mut fake, &["ls", "--format", "tsv"]).unwrap(),
+            "api\t1\t1\t1700000000\t/work/api\n"
+        );
+    }
+
+    #[test]
+    fn ls_help_documents_the_schema() {
+        let help = Cli::command()
+            .find_subcommand_mut("ls")
+            .unwrap()
+            .render_long_help()
+            .to_string();
+        for field in ["name", "windows", "attached", "activity", "path"] {
+            assert!(
+                help.contains(&format!("  {} ", field)),
+                "{} is not documented",
+                field
+            );
+        }
+    }
+
+    #[test]
+    fn new_rejects_invalid_names_and_unknown_templates() {
+        let mut fake = FakeBackend::new().with_templates(&["rust"]);
+        let calls = fake.calls();
+
+        assert_eq!(
+            invalid(run_args(&mut fake, &["new", "a:b"])),
+            "':' is not allowed in session names"
+        );
+        assert_eq!(
+            invalid(run_args(&mut fake, &["new", "web", "--template", "go"])),
+            "no template named 'go'"
+        );
+        assert!(calls.borrow().is_empty());
+    }
+
+    #[test]
+    fn kill_and_rename_need_a_running_session() {
+        let mut fake = FakeBackend::new().with_sessions(&["api"]);
+        let calls = fake.calls();
+
+        assert_eq!(
+            invalid(run_args(&mut fake, &["kill", "web"])),
+            "no session named 'web'"
+        );
+        assert_eq!(
+            invalid(run_args(&mut fake, &["rename", "web", "site"])),
+            "no session named 'web'"
+        );
+        assert!(calls.borrow().is_empty());
+    }
+}
@@ -0,0 +1,37 @@
+use crate::theme::Theme;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::ListState,
+};
+
+use super::{input_box::draw_input_box, list_widget::styled_list};
+
+/// A path field with the matching subdirectories listed below it.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_dir_browser(
+    f: &mut Frame,
+    area: Rect,
+    input: &str,
+    completions: &[String],
+    state: &mut ListState,
+    selected: usize,
+    problem: Option<&str>,
+    theme: &Theme,
+) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+
+    draw_input_box(f, chunks[0], "Directory", input, problem, true, theme);
+    styled_list(
+        f,
+        chunks[1],
+        completions,
+        state,
+        "Subdirectories",
+        theme,
+        selected,
+    );
+}
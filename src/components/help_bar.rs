@@ -1,5 +1,5 @@
 
-use ratatui::{ widgets::Paragraph, layout::Rect, Frame, backend::Backend, style::Style };
+use ratatui::{ widgets::Paragraph, layout::Rect, Frame, style::Style };
 use crate::theme::Theme;
 
 /// Draws a help bar (bottom hint area)
@@ -1,22 +1,29 @@
 use ratatui::{
     layout::Rect,
-    widgets::{Block, Borders, Paragraph},
+    widgets::Paragraph,
     style::Style,
-    Frame, backend::Backend,
+    Frame,
 };
 use crate::theme::Theme;
 
-/// Renders an input box (for user text input)
-pub fn draw_input_box<B: Backend>(
+/// Renders an input box (for user text input). The focused box shows a
+/// cursor; `footer` carries a hint or a validation problem.
+pub fn draw_input_box(
     f: &mut Frame,
     area: Rect,
     label: &str,
     value: &str,
+    footer: Option<&str>,
+    focused: bool,
     theme: &Theme,
 ) {
-    let text = format!("{}: {}", label, value);
+    let text = if focused { format!("{}▏", value) } else { value.to_string() };
+    let mut block = theme.block_style(label, footer);
+    if focused {
+        block = block.border_style(Style::default().fg(theme.highlight.fg));
+    }
     let input = Paragraph::new(text)
-        .block(Block::default().borders(Borders::ALL).title("Input"))
+        .block(block)
         .style(Style::default().fg(theme.text_color));
     f.render_widget(input, area);
 }
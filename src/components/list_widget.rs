@@ -1,25 +1,205 @@
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use ratatui::{
-    prelude::Frame, layout::Rect, style::{Color, Style}, widgets::{Block, Borders, List, ListItem, ListState}
+    prelude::Frame, layout::Rect, style::{Modifier, Style}, text::{Line, Span}, widgets::{List, ListItem, ListState}
 };
 
+use crate::fuzzy::Matched;
+use crate::templates::layout::shorten_home;
+use crate::sources::{Directory, DirectoryKind, Entry};
+use crate::templates::Template;
 use crate::theme::Theme;
+use crate::tmux::{Pane, Session, Window};
 
-pub fn styled_list<'a>(
+/// Anything `styled_list` can render as a row.
+pub trait ListEntry {
+    fn label(&self) -> String;
+
+    /// Extra information shown dimmed after the label.
+    fn detail(&self) -> Option<String> {
+        None
+    }
+
+    /// Char indices of `label` to highlight, e.g. fuzzy match positions.
+    fn highlights(&self) -> &[usize] {
+        &[]
+    }
+}
+
+impl<T: ListEntry> ListEntry for Matched<T> {
+    fn label(&self) -> String {
+        self.item.label()
+    }
+
+    fn detail(&self) -> Option<String> {
+        self.item.detail()
+    }
+
+    fn highlights(&self) -> &[usize] {
+        &self.positions
+    }
+}
+
+/// A row with a leading mark showing whether it was picked in Visual mode.
+pub struct Marked<T> {
+    pub item: T,
+    pub marked: bool,
+    /// `item`'s highlights, moved past the mark.
+    positions: Vec<usize>,
+}
+
+impl<T: ListEntry> Marked<T> {
+    pub fn new(item: T, marked: bool) -> Self {
+        let positions = item.highlights().iter().map(|p| p + 2).collect();
+        Marked { item, marked, positions }
+    }
+}
+
+impl<T: ListEntry> ListEntry for Marked<T> {
+    fn label(&self) -> String {
+        let mark = if self.marked { '+' } else { ' ' };
+        format!("{} {}", mark, self.item.label())
+    }
+
+    fn detail(&self) -> Option<String> {
+        self.item.detail()
+    }
+
+    fn highlights(&self) -> &[usize] {
+        &self.positions
+    }
+}
+
+impl ListEntry for String {
+    fn label(&self) -> String {
+        self.clone()
+    }
+}
+
+impl ListEntry for &str {
+    fn label(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl ListEntry for Template {
+    fn label(&self) -> String {
+        self.name.clone()
+    }
+
+    fn detail(&self) -> Option<String> {
+        (!self.engine.is_empty()).then(|| self.engine.clone())
+    }
+}
+
+impl ListEntry for Session {
+    fn label(&self) -> String {
+        self.name.clone()
+    }
+
+    fn detail(&self) -> Option<String> {
+        let mut parts = vec![format!("{}w", self.windows)];
+        if self.is_attached() {
+            parts.push("attached".to_string());
+        }
+        if let Some(group) = &self.group {
+            parts.push(format!("group {}", group));
+        }
+        if self.activity > 0 {
+            parts.push(format_age(self.activity));
+        }
+        if !self.path.is_empty() {
+            parts.push(shorten_home(&self.path));
+        }
+        Some(parts.join(" · "))
+    }
+}
+
+impl ListEntry for Directory {
+    fn label(&self) -> String {
+        self.name.clone()
+    }
+
+    fn detail(&self) -> Option<String> {
+        let kind = match self.kind {
+            DirectoryKind::Project => "project".to_string(),
+            DirectoryKind::Zoxide { score } => format!("zoxide {:.1}", score),
+        };
+        Some(format!("{} · {}", kind, shorten_home(&self.path)))
+    }
+}
+
+impl ListEntry for Entry {
+    fn label(&self) -> String {
+        match self {
+            Entry::Session(s) => s.label(),
+            Entry::Directory(d) => d.label(),
+        }
+    }
+
+    fn detail(&self) -> Option<String> {
+        match self {
+            Entry::Session(s) => s.detail(),
+            Entry::Directory(d) => d.detail(),
+        }
+    }
+}
+
+impl ListEntry for Window {
+    fn label(&self) -> String {
+        format!("{}: {}", self.index, self.name)
+    }
+
+    fn detail(&self) -> Option<String> {
+        let mut parts = vec![format!("{}p", self.panes), self.command.clone()];
+        if self.active {
+            parts.push("active".to_string());
+        }
+        parts.push(self.layout.clone());
+        Some(parts.join(" · "))
+    }
+}
+
+impl ListEntry for Pane {
+    fn label(&self) -> String {
+        format!("{}: {}", self.index, self.command)
+    }
+
+    fn detail(&self) -> Option<String> {
+        let mut parts = vec![format!("{}x{}", self.width, self.height)];
+        if self.active {
+            parts.push("active".to_string());
+        }
+        if !self.title.is_empty() {
+            parts.push(self.title.clone());
+        }
+        if !self.path.is_empty() {
+            parts.push(shorten_home(&self.path));
+        }
+        Some(parts.join(" · "))
+    }
+}
+
+pub fn styled_list<T: ListEntry>(
     f: &mut Frame,
     area: Rect,
-    items: Vec<String>,
+    items: &[T],
     state: &mut ListState,
-    title: &'a str,
+    title: &str,
     theme: &Theme,
     selected_index: usize,
 ) {
     let list_items: Vec<ListItem> = items
         .iter()
         .enumerate()
-        .map(|(i, s)| {
+        .map(|(i, item)| {
             let prefix = if i == selected_index { "\u{2009}" } else { "\u{2009} " };
-            ListItem::new(format!("{} {}", prefix, s))
+            let mut spans = vec![Span::raw(format!("{} ", prefix))];
+            spans.extend(highlighted_label(&item.label(), item.highlights(), theme));
+            if let Some(detail) = item.detail() {
+                spans.push(Span::styled(format!("  {}", detail), Style::default().fg(theme.border_color)));
+            }
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
@@ -32,3 +212,42 @@ pub fn styled_list<'a>(
 
     f.render_stateful_widget(list, area, state);
 }
+
+/// Split `label` into spans, styling the chars at `positions` with the theme's match color.
+fn highlighted_label(label: &str, positions: &[usize], theme: &Theme) -> Vec<Span<'static>> {
+    if positions.is_empty() {
+        return vec![Span::raw(label.to_string())];
+    }
+    let matched = Style::default().fg(theme.match_color).add_modifier(Modifier::BOLD);
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, c) in label.chars().enumerate() {
+        let is_matched = positions.contains(&i);
+        if is_matched != run_matched && !run.is_empty() {
+            let text = std::mem::take(&mut run);
+            spans.push(if run_matched { Span::styled(text, matched) } else { Span::raw(text) });
+        }
+        run_matched = is_matched;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(if run_matched { Span::styled(run, matched) } else { Span::raw(run) });
+    }
+    spans
+}
+
+/// "5m ago" style rendering of a unix timestamp.
+fn format_age(timestamp: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(timestamp);
+    let secs = now.saturating_sub(timestamp);
+    match secs {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
@@ -2,3 +2,5 @@ pub mod list_widget;
 pub mod help_bar;
 pub mod popup;
 pub mod input_box;
+pub mod dir_browser;
+pub mod preview;
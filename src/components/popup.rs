@@ -1,13 +1,12 @@
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
-    text::{Span, Line},
-    widgets::{Block, Borders, BorderType, Clear, Paragraph},
+    style::Style,
+    widgets::{Clear, Paragraph, Wrap},
 };
 use crate::theme::Theme;
 
-pub fn draw_popup(f: &mut Frame, area: Rect, title: &str, input: &str, theme: &Theme) {
+pub fn draw_popup(f: &mut Frame, area: Rect, title: &str, input: &str, footer: &str, theme: &Theme) {
     // popup size
     let popup_area = centered_rect(50, 25, area);
 
@@ -19,7 +18,7 @@ pub fn draw_popup(f: &mut Frame, area: Rect, title: &str, input: &str, theme: &T
         .style(Style::default().fg(theme.text_color))
         .alignment(Alignment::Left)
         .block(
-            theme.block_style(title, Some("")) 
+            theme.block_style(title, Some(footer))
 
         );
 
@@ -48,3 +47,16 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 
     horizontal[1]
 }
+
+/// Draws an error message on top of the current view.
+pub fn draw_error_popup(f: &mut Frame, area: Rect, message: &str, theme: &Theme) {
+    let popup_area = centered_rect(60, 30, area);
+    f.render_widget(Clear, popup_area);
+
+    let paragraph = Paragraph::new(message)
+        .style(Style::default().fg(theme.text_color))
+        .wrap(Wrap { trim: true })
+        .block(theme.popup_block_style(" Error ").title_bottom(" press any key "));
+
+    f.render_widget(paragraph, popup_area);
+}
@@ -0,0 +1,21 @@
+use crate::theme::Theme;
+use ansi_to_tui::IntoText;
+use ratatui::{Frame, layout::Rect, style::Style, text::Text, widgets::Paragraph};
+
+/// Renders captured pane output (with ANSI colors) next to the session list.
+pub fn draw_preview(f: &mut Frame, area: Rect, title: &str, captured: &str, theme: &Theme) {
+    let text = captured
+        .into_text()
+        .unwrap_or_else(|_| Text::raw(captured.to_string()));
+
+    // keep the bottom of the pane in view, like tmux does
+    let visible = area.height.saturating_sub(2) as usize;
+    let scroll = text.lines.len().saturating_sub(visible) as u16;
+
+    let paragraph = Paragraph::new(text)
+        .style(Style::default().fg(theme.text_color))
+        .scroll((scroll, 0))
+        .block(theme.block_style("Preview", Some(title)));
+
+    f.render_widget(paragraph, area);
+}
@@ -1,61 +1,349 @@
 
-use serde::{Deserialize, Serialize};
-use std::{fs, path::PathBuf};
-use crate::theme::{Theme, ThemeColor};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::BTreeMap, env, ffi::OsString, fmt, fs, io, path::PathBuf};
+use toml::{Table, Value};
+use crate::theme::Theme;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub settings: Settings,
     pub theme_config: ThemeConfig,
+    pub projects: ProjectsConfig,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
+    /// Not read anywhere yet; kept so existing configs stay valid.
     pub show_icons: bool,
+    /// Screen the picker starts on: `main`, `sessions`, `create` or `projects`.
     pub default_view: String,
     pub template_engine: String,
+    /// Quit the picker after switching the tmux client to a session.
+    pub exit_after_switch: bool,
+    /// Offer zoxide's directories next to running sessions.
+    pub zoxide: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            show_icons: true,
+            default_view: "main".into(),
+            template_engine: "native, tmuxifier".into(),
+            exit_after_switch: true,
+            zoxide: true,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ThemeConfig {
     pub border_color: String,
     pub highlight: ThemeHighlight,
     pub text_color: String,
+    pub match_color: String,
     pub border_type: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self {
+            border_color: "#6c7086".into(),
+            highlight: ThemeHighlight::default(),
+            text_color: "#cdd6f4".into(),
+            match_color: "#f9e2af".into(),
+            border_type: Some("Rounded".into()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ThemeHighlight {
     pub bg: String,
     pub fg: String,
 }
 
+impl Default for ThemeHighlight {
+    fn default() -> Self {
+        Self {
+            bg: "#89b4fa".into(),
+            fg: "#1e1e2e".into(),
+        }
+    }
+}
 
-impl Config {
-    pub fn load() -> Self {
-        let config_path = Self::path();
-        if config_path.exists() {
-            let content = fs::read_to_string(&config_path).unwrap_or_default();
-            toml::from_str(&content).unwrap_or_else(|_| Self::default())
-        } else {
-            let cfg = Self::default();
-            // cfg.save();
-            cfg
+/// Where to look for project directories to offer next to running sessions.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectsConfig {
+    /// Directories to scan, e.g. `["~/code", "~/work"]`. Nothing is scanned when empty.
+    pub roots: Vec<String>,
+    /// How many levels below a root to look for projects.
+    pub max_depth: usize,
+    /// Globs matched against directory names and full paths.
+    pub ignore: Vec<String>,
+    /// A directory containing any of these is a project.
+    pub markers: Vec<String>,
+}
+
+impl Default for ProjectsConfig {
+    fn default() -> Self {
+        Self {
+            roots: vec![],
+            max_depth: 3,
+            ignore: vec!["node_modules".into(), "target".into(), ".*".into()],
+            markers: vec![".git".into()],
+        }
+    }
+}
+
+
+/// Comment written above each key by `Config::to_documented_toml`.
+const KEY_DOCS: &[(&str, &str)] = &[
+    ("settings.show_icons", "Not used yet; icons in lists are planned."),
+    ("settings.default_view", "Screen to start on: main, sessions, create or projects."),
+    ("settings.template_engine", "Comma-separated engines: native, tmuxifier, tmuxinator, tmuxp, or all."),
+    ("settings.exit_after_switch", "Quit after switching the tmux client to a session."),
+    ("settings.zoxide", "Offer zoxide's directories next to running sessions."),
+    ("theme_config.border_color", "Colors are #RRGGBB."),
+    ("theme_config.match_color", "Characters matching the search."),
+    ("theme_config.border_type", "Plain, Rounded, Double or Thick."),
+    ("theme_config.highlight.bg", "Background of the selected row."),
+    ("theme_config.highlight.fg", "Text of the selected row."),
+    ("projects.roots", "Directories to scan for projects, e.g. [\"~/code\"]."),
+    ("projects.max_depth", "How many levels below a root to look for projects."),
+    ("projects.ignore", "Globs matched against directory names and full paths."),
+    ("projects.markers", "A directory containing any of these is a project."),
+];
+
+/// Prefix of the environment variables overriding config values, e.g.
+/// `TMUX_SESSIONIBER_SETTINGS_DEFAULT_VIEW=sessions`.
+pub const ENV_PREFIX: &str = "TMUX_SESSIONIBER_";
+
+/// Name of the project-local config, looked up from the working directory.
+pub const PROJECT_FILE: &str = ".sessioniber.toml";
+
+/// Where an effective config value came from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Origin {
+    Default,
+    File(PathBuf),
+    /// The environment variable that set it.
+    Env(String),
+    /// `--set` or another command-line flag.
+    Cli,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Default => write!(f, "default"),
+            Origin::File(path) => write!(f, "{}", path.display()),
+            Origin::Env(var) => write!(f, "${}", var),
+            Origin::Cli => write!(f, "command line"),
+        }
+    }
+}
+
+/// Something wrong with a config layer. Only the affected value is skipped;
+/// the rest of the layer still applies.
+#[derive(Debug)]
+pub struct ConfigProblem {
+    pub origin: Origin,
+    /// 1-based line and column in the file, when known.
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl ConfigProblem {
+    pub fn new(origin: Origin, message: impl Into<String>) -> Self {
+        ConfigProblem { origin, position: None, message: message.into() }
+    }
+
+    fn from_toml(origin: Origin, e: &toml::de::Error, content: &str) -> Self {
+        ConfigProblem {
+            origin,
+            position: e.span().map(|span| line_column(content, span.start)),
+            message: e.message().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.position {
+            Some((line, column)) => write!(f, "{}: line {}, column {}: {}", self.origin, line, column, self.message),
+            None => write!(f, "{}: {}", self.origin, self.message),
         }
     }
+}
+
+/// The merged config, where each value came from, and what was skipped.
+pub struct LoadedConfig {
+    pub config: Config,
+    /// Origin of every value, by dotted key (`settings.default_view`).
+    pub origins: BTreeMap<String, Origin>,
+    pub problems: Vec<ConfigProblem>,
+}
+
+impl LoadedConfig {
+    /// Every effective value as `(dotted key, TOML value, origin)`.
+    pub fn values(&self) -> Vec<(String, String, Origin)> {
+        let mut values = Vec::new();
+        if let Ok(Value::Table(table)) = Value::try_from(&self.config) {
+            flatten(&table, "", &mut values);
+        }
+        values
+            .into_iter()
+            .map(|(key, value)| {
+                let origin = self.origins.get(&key).cloned().unwrap_or(Origin::Default);
+                (key, value.to_string(), origin)
+            })
+            .collect()
+    }
+}
+
+impl Config {
+    /// Load the config by merging, later layers winning: the defaults, the
+    /// system file, the user file (`Config::path`), the nearest
+    /// `.sessioniber.toml` above the working directory, `TMUX_SESSIONIBER_*`
+    /// variables, then `overrides` (`key=value` from the command line).
+    /// Invalid values are skipped and reported.
+    pub fn load(overrides: &[String]) -> LoadedConfig {
+        let files = [Some(Self::system_path()), Some(Self::path()), Self::project_path()]
+            .into_iter()
+            .flatten()
+            .map(|path| {
+                let content = fs::read_to_string(&path);
+                (path, content)
+            })
+            .collect();
+        Self::load_from(files, env::vars_os(), overrides)
+    }
+
+    /// `load` from the given config `files` (path and what reading it
+    /// returned, lowest precedence first) and environment `vars`.
+    fn load_from(
+        files: Vec<(PathBuf, io::Result<String>)>,
+        vars: impl IntoIterator<Item = (OsString, OsString)>,
+        overrides: &[String],
+    ) -> LoadedConfig {
+        let mut merged = match Value::try_from(Self::default()) {
+            Ok(Value::Table(table)) => table,
+            _ => Table::new(),
+        };
+        let mut origins = BTreeMap::new();
+        let mut problems = Vec::new();
 
-    pub fn save(&self) {
+        for (path, content) in files {
+            let origin = Origin::File(path);
+            let layer = match content {
+                Ok(content) => parse_layer(&content, origin.clone(), &mut problems),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => {
+                    problems.push(ConfigProblem::new(origin, e.to_string()));
+                    continue;
+                }
+            };
+            merge(&mut merged, layer, "", &origin, &mut origins);
+        }
+
+        // keys are matched against the defaults: the variable for
+        // `settings.default_view` is TMUX_SESSIONIBER_SETTINGS_DEFAULT_VIEW
+        let mut defaults = Vec::new();
+        if let Ok(Value::Table(table)) = Value::try_from(Self::default()) {
+            flatten(&table, "", &mut defaults);
+        }
+        for (var, raw) in vars {
+            let (Some(var), Some(raw)) = (var.to_str(), raw.to_str()) else {
+                // other programs' variables may hold anything
+                let var = var.to_string_lossy();
+                if var.starts_with(ENV_PREFIX) {
+                    problems.push(ConfigProblem::new(Origin::Env(var.into_owned()), "not valid UTF-8"));
+                }
+                continue;
+            };
+            let Some(name) = var.strip_prefix(ENV_PREFIX) else { continue };
+            let origin = Origin::Env(var.to_string());
+            match defaults.iter().map(|(key, _)| key).find(|key| key.replace('.', "_").eq_ignore_ascii_case(name)) {
+                Some(key) => {
+                    let layer = check_layer(assignment(key, raw), &origin, None, &mut problems);
+                    merge(&mut merged, layer, "", &origin, &mut origins);
+                }
+                None => problems.push(ConfigProblem::new(origin, "no such config key")),
+            }
+        }
+
+        for assignment_str in overrides {
+            let Some((key, raw)) = assignment_str.split_once('=') else {
+                problems.push(ConfigProblem::new(Origin::Cli, format!("expected KEY=VALUE, got `{}`", assignment_str)));
+                continue;
+            };
+            let layer = check_layer(assignment(key.trim(), raw.trim()), &Origin::Cli, None, &mut problems);
+            merge(&mut merged, layer, "", &Origin::Cli, &mut origins);
+        }
+
+        let config = Value::Table(merged).try_into().unwrap_or_default();
+        LoadedConfig { config, origins, problems }
+    }
+    /// Write this config to `Config::path`, with a comment above every key.
+    pub fn save(&self) -> io::Result<()> {
         let config_path = Self::path();
         if let Some(parent) = config_path.parent() {
-            fs::create_dir_all(parent).unwrap();
+            fs::create_dir_all(parent)?;
         }
-        let toml_str = toml::to_string_pretty(&self).unwrap();
-        fs::write(config_path, toml_str).unwrap();
+        fs::write(config_path, self.to_documented_toml())
     }
 
-    fn path() -> PathBuf {
+    /// This config as TOML, each key preceded by its description.
+    pub fn to_documented_toml(&self) -> String {
+        let toml_str = toml::to_string_pretty(self).unwrap_or_default();
+        let mut out = String::new();
+        let mut section = String::new();
+        for line in toml_str.lines() {
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = header.to_string();
+            } else if let Some((key, _)) = line.split_once(" = ") {
+                let path = format!("{}.{}", section, key);
+                if let Some((_, doc)) = KEY_DOCS.iter().find(|(k, _)| *k == path) {
+                    out.push_str(&format!("# {}\n", doc));
+                }
+            }
+            out.push_str(line);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// The user config file.
+    pub fn path() -> PathBuf {
+        Self::dir().join("config.toml")
+    }
+
+    /// Machine-wide defaults, read before the user file.
+    pub fn system_path() -> PathBuf {
+        PathBuf::from("/etc/tmux-sessioniber/config.toml")
+    }
+
+    /// The nearest `.sessioniber.toml` in the working directory or above it.
+    pub fn project_path() -> Option<PathBuf> {
+        let cwd = env::current_dir().ok()?;
+        cwd.ancestors().map(|dir| dir.join(PROJECT_FILE)).find(|path| path.is_file())
+    }
+
+    /// Directory holding `config.toml` and the native `layouts`.
+    pub fn dir() -> PathBuf {
         let dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-        dir.join("tmux-sessioniber/config.toml")
+        dir.join("tmux-sessioniber")
+    }
+
+    /// Where the native template engine looks for `*.toml` layouts.
+    pub fn layouts_dir() -> PathBuf {
+        Self::dir().join("layouts")
     }
 
     pub fn theme(&self) -> Theme {
@@ -63,27 +351,171 @@ impl Config {
     }
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            settings: Settings {
-                show_icons: true,
-                default_view: "main".into(),
-                template_engine: "tmuxifier".into(),
-            },
-            theme_config: ThemeConfig {
-                border_color: "#6c7086".into(),
-                highlight: ThemeHighlight {
-                    bg: "#89b4fa".into(),
-                    fg: "#1e1e2e".into(),
-                },
-                text_color: "#cdd6f4".into(),
-                border_type: Some("Rounded".into()),
-            },
+/// Read one config file into a table of its valid values.
+fn parse_layer(content: &str, origin: Origin, problems: &mut Vec<ConfigProblem>) -> Table {
+    match content.parse::<Table>() {
+        Ok(table) => check_layer(table, &origin, Some(content), problems),
+        Err(e) => {
+            // a syntax error: nothing to salvage
+            problems.push(ConfigProblem::from_toml(origin, &e, content));
+            Table::new()
+        }
+    }
+}
+
+/// `table` without the values `Config` rejects and the keys it doesn't
+/// have, each reported as a problem. `content` is the file the table came
+/// from, used to locate the offending keys.
+fn check_layer(table: Table, origin: &Origin, content: Option<&str>, problems: &mut Vec<ConfigProblem>) -> Table {
+    let locate = |path: &str| {
+        let key = path.rsplit('.').next().unwrap_or(path);
+        content.and_then(|content| find_key(content, key, path))
+    };
+
+    let mut good = Table::new();
+    let mut rejected = Vec::new();
+    keep_valid::<Config>(&mut good, &[], &table, &mut rejected);
+    for (path, message) in rejected {
+        problems.push(ConfigProblem {
+            origin: origin.clone(),
+            position: locate(&path),
+            message: format!("`{}`: {}", path, message),
+        });
+    }
+
+    let mut unknown = Vec::new();
+    if let Ok(Value::Table(known)) = Value::try_from(Config::default()) {
+        unknown_keys(&good, &known, "", &mut unknown);
+    }
+    for path in unknown {
+        // typos, most likely
+        problems.push(ConfigProblem {
+            origin: origin.clone(),
+            position: locate(&path),
+            message: format!("unknown key `{}`", path),
+        });
+        set(&mut good, &path.split('.').collect::<Vec<_>>(), None);
+    }
+    good
+}
+
+/// Copy `layer` over `base`, recording `origin` for every value it sets.
+fn merge(base: &mut Table, layer: Table, prefix: &str, origin: &Origin, origins: &mut BTreeMap<String, Origin>) {
+    for (key, value) in layer {
+        let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(inner)), Value::Table(value)) => merge(inner, value, &path, origin, origins),
+            (_, value) => {
+                origins.insert(path, origin.clone());
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// The leaf values of `table`, by dotted key.
+fn flatten(table: &Table, prefix: &str, out: &mut Vec<(String, Value)>) {
+    for (key, value) in table {
+        let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match value {
+            Value::Table(inner) => flatten(inner, &path, out),
+            value => out.push((path, value.clone())),
+        }
+    }
+}
+
+/// A table setting the dotted `key` to `raw`, read as a TOML value
+/// (`true`, `3`, `["~/code"]`) when it is one and as a string otherwise.
+fn assignment(key: &str, raw: &str) -> Table {
+    let mut value = format!("v = {}", raw)
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut t| t.remove("v"))
+        .unwrap_or_else(|| Value::String(raw.to_string()));
+    for part in key.split('.').rev() {
+        let mut table = Table::new();
+        table.insert(part.to_string(), value);
+        value = Value::Table(table);
+    }
+    match value {
+        Value::Table(table) => table,
+        _ => Table::new(),
+    }
+}
+
+/// Copy the entries of `table` (found at `path` in the file) into `root`
+/// one by one, dropping those that stop `root` from deserializing as `T`.
+/// A rejected table is retried entry by entry.
+fn keep_valid<T: DeserializeOwned>(root: &mut Table, path: &[&str], table: &Table, rejected: &mut Vec<(String, String)>) {
+    for (key, value) in table {
+        let mut at = path.to_vec();
+        at.push(key);
+        set(root, &at, Some(value.clone()));
+        let Err(e) = Value::Table(root.clone()).try_into::<T>() else {
+            continue;
+        };
+        set(root, &at, None);
+        if let Value::Table(inner) = value {
+            set(root, &at, Some(Value::Table(Table::new())));
+            keep_valid::<T>(root, &at, inner, rejected);
+        } else {
+            rejected.push((at.join("."), e.message().to_string()));
         }
     }
 }
 
+/// Insert (or with `None`, remove) the value at the dotted `path` of `root`.
+fn set(root: &mut Table, path: &[&str], value: Option<Value>) {
+    let Some((last, parents)) = path.split_last() else { return };
+    let mut table = root;
+    for key in parents {
+        match table.get_mut(*key) {
+            Some(Value::Table(inner)) => table = inner,
+            _ => return,
+        }
+    }
+    match value {
+        Some(value) => {
+            table.insert(last.to_string(), value);
+        }
+        None => {
+            table.remove(*last);
+        }
+    }
+}
+
+/// Collect the dotted keys of `table` that `known` (the defaults) doesn't have.
+fn unknown_keys(table: &Table, known: &Table, prefix: &str, found: &mut Vec<String>) {
+    for (key, value) in table {
+        let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match (value, known.get(key)) {
+            (_, None) => found.push(path),
+            (Value::Table(inner), Some(Value::Table(known_inner))) => unknown_keys(inner, known_inner, &path, found),
+            _ => {}
+        }
+    }
+}
+
+/// Where `key = ...` or the `[path]` header first appears in `content`.
+fn find_key(content: &str, key: &str, path: &str) -> Option<(usize, usize)> {
+    content.lines().enumerate().find_map(|(i, line)| {
+        let trimmed = line.trim_start();
+        let is_assignment = trimmed
+            .strip_prefix(key)
+            .is_some_and(|rest| rest.trim_start().starts_with('='));
+        let is_header = trimmed.trim_end() == format!("[{}]", path);
+        (is_assignment || is_header).then(|| (i + 1, line.len() - trimmed.len() + 1))
+    })
+}
+
+/// 1-based line and column of the byte `offset` in `content`.
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+
 
 // impl Config {
 //     pub fn theme(&self) -> Theme {
@@ -93,3 +525,213 @@ impl Default for Config {
 //         }
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn origin() -> Origin {
+        Origin::File(PathBuf::from("config.toml"))
+    }
+
+    /// Each problem as `(line, column, message)`.
+    fn positions(problems: &[ConfigProblem]) -> Vec<(usize, usize, &str)> {
+        problems
+            .iter()
+            .map(|p| {
+                let (line, column) = p.position.unwrap_or((0, 0));
+                (line, column, p.message.as_str())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn partial_file_keeps_its_valid_values() {
+        let content = "[settings]\nzoxide = false\n  exit_after_switch = \"yes\"\n\n[projects]\nmax_depth = 5\nroots = \"~/code\"\n";
+        let mut problems = Vec::new();
+        let layer = parse_layer(content, origin(), &mut problems);
+
+        let mut values = Vec::new();
+        flatten(&layer, "", &mut values);
+        let keys: Vec<&str> = values.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, ["projects.max_depth", "settings.zoxide"]);
+
+        let found = positions(&problems);
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].0, found[0].1), (7, 1));
+        assert!(found[0].2.starts_with("`projects.roots`: "), "{}", found[0].2);
+        assert_eq!((found[1].0, found[1].1), (3, 3));
+        assert!(found[1].2.starts_with("`settings.exit_after_switch`: "), "{}", found[1].2);
+    }
+
+    #[test]
+    fn unknown_keys_are_dropped_and_located() {
+        let content = "[settings]\nzoxyde = true\n\n[colors]\nbg = \"#000000\"\n";
+        let mut problems = Vec::new();
+        let layer = parse_layer(content, origin(), &mut problems);
+
+        assert_eq!(layer.get("settings"), Some(&Value::Table(Table::new())));
+        assert_eq!(positions(&problems), [(4, 1, "unknown key `colors`"), (2, 1, "unknown key `settings.zoxyde`")]);
+    }
+
+    #[test]
+    fn syntax_error_is_located() {
+        let content = "[settings]\nzoxide = \n";
+        let mut problems = Vec::new();
+        let layer = parse_layer(content, origin(), &mut problems);
+
+        assert!(layer.is_empty());
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].position, Some((2, 10)));
+        assert!(problems[0].to_string().starts_with("config.toml: line 2, column 10: "));
+    }
+
+    #[test]
+    fn whole_bad_table_is_retried_key_by_key() {
+        let mut good = Table::new();
+        let mut rejected = Vec::new();
+        let table: Table = "[theme_config.highlight]\nbg = 1\nfg = \"#ffffff\"\n".parse().unwrap();
+        keep_valid::<Config>(&mut good, &[], &table, &mut rejected);
+
+        let mut values = Vec::new();
+        flatten(&good, "", &mut values);
+        assert_eq!(values, [("theme_config.highlight.fg".to_string(), Value::String("#ffffff".to_string()))]);
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].0, "theme_config.highlight.bg");
+    }
+
+    fn file(path: &str, content: &str) -> (PathBuf, io::Result<String>) {
+        (PathBuf::from(path), Ok(content.to_string()))
+    }
+
+    fn vars(vars: &[(&str, &str)]) -> Vec<(OsString, OsString)> {
+        vars.iter().map(|(k, v)| (OsString::from(k), OsString::from(v))).collect()
+    }
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn later_layers_win() {
+        let files = vec![
+            file("/etc/config.toml", "[settings]\ndefault_view = \"create\"\nzoxide = false\nshow_icons = false\n"),
+            file("/home/config.toml", "[settings]\ndefault_view = \"sessions\"\nzoxide = true\n"),
+            file("/code/.sessioniber.toml", "[settings]\ndefault_view = \"projects\"\n\n[projects]\nmax_depth = 1\n"),
+        ];
+        let env = vars(&[("TMUX_SESSIONIBER_SETTINGS_DEFAULT_VIEW", "main"), ("TMUX_SESSIONIBER_PROJECTS_MAX_DEPTH", "2")]);
+        let loaded = Config::load_from(files, env, &strings(&["projects.max_depth=4"]));
+
+        assert!(loaded.problems.is_empty(), "{:?}", loaded.problems);
+        let settings = &loaded.config.settings;
+        assert_eq!(settings.default_view, "main");
+        assert!(settings.zoxide);
+        assert!(!settings.show_icons);
+        assert_eq!(loaded.config.projects.max_depth, 4);
+    }
+
+    #[test]
+    fn origins_name_the_winning_layer() {
+        let files = vec![
+            file("/etc/config.toml", "[settings]\nshow_icons = false\n"),
+            file("/home/config.toml", "[settings]\nzoxide = false\n"),
+        ];
+        let env = vars(&[("TMUX_SESSIONIBER_SETTINGS_DEFAULT_VIEW", "sessions")]);
+        let loaded = Config::load_from(files, env, &strings(&["settings.zoxide=true"]));
+
+        let origin = |key: &str| loaded.origins.get(key).cloned().unwrap_or(Origin::Default);
+        assert_eq!(origin("settings.show_icons"), Origin::File(PathBuf::from("/etc/config.toml")));
+        assert_eq!(origin("settings.default_view"), Origin::Env("TMUX_SESSIONIBER_SETTINGS_DEFAULT_VIEW".to_string()));
+        assert_eq!(origin("settings.zoxide"), Origin::Cli);
+        assert_eq!(origin("projects.max_depth"), Origin::Default);
+        let shown = loaded.values().into_iter().find(|(key, _, _)| key == "settings.default_view");
+        assert_eq!(shown.map(|(_, value, _)| value), Some("\"sessions\"".to_string()));
+    }
+
+    #[test]
+    fn set_values_are_read_as_toml_or_strings() {
+        let overrides = strings(&["projects.roots=[\"~/code\", \"~/work\"]", " settings.default_view = sessions ", "settings.zoxide=false"]);
+        let loaded = Config::load_from(vec![], vec![], &overrides);
+
+        assert!(loaded.problems.is_empty(), "{:?}", loaded.problems);
+        assert_eq!(loaded.config.projects.roots, ["~/code", "~/work"]);
+        assert_eq!(loaded.config.settings.default_view, "sessions");
+        assert!(!loaded.config.settings.zoxide);
+    }
+
+    #[test]
+    fn bad_overrides_are_reported() {
+        let overrides = strings(&["settings.zoxide", "settings.zoxide=maybe", "settings.nope=1"]);
+        let loaded = Config::load_from(vec![], vec![], &overrides);
+
+        let messages: Vec<String> = loaded.problems.iter().map(|p| p.to_string()).collect();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0], "command line: expected KEY=VALUE, got `settings.zoxide`");
+        assert!(messages[1].starts_with("command line: `settings.zoxide`: "), "{}", messages[1]);
+        assert_eq!(messages[2], "command line: unknown key `settings.nope`");
+        assert!(loaded.config.settings.zoxide);
+    }
+
+    #[test]
+    fn unknown_env_variable_is_reported() {
+        let env = vars(&[("TMUX_SESSIONIBER_SETTINGS_ZOXYDE", "false"), ("TMUX", "/tmp/tmux-0/default"), ("PATH", "/bin")]);
+        let loaded = Config::load_from(vec![], env, &[]);
+
+        let messages: Vec<String> = loaded.problems.iter().map(|p| p.to_string()).collect();
+        assert_eq!(messages, ["$TMUX_SESSIONIBER_SETTINGS_ZOXYDE: no such config key"]);
+        assert!(loaded.config.settings.zoxide);
+    }
+
+    #[test]
+    fn env_names_ignore_case_and_values_are_checked() {
+        let env = vars(&[("TMUX_SESSIONIBER_settings_zoxide", "false"), ("TMUX_SESSIONIBER_PROJECTS_MAX_DEPTH", "deep")]);
+        let loaded = Config::load_from(vec![], env, &[]);
+
+        assert!(!loaded.config.settings.zoxide);
+        assert_eq!(loaded.config.projects.max_depth, 3);
+        assert_eq!(loaded.problems.len(), 1);
+        assert_eq!(loaded.problems[0].origin, Origin::Env("TMUX_SESSIONIBER_PROJECTS_MAX_DEPTH".to_string()));
+    }
+
+    #[test]
+    fn documented_defaults_read_back_as_the_defaults() {
+        let documented = Config::default().to_documented_toml();
+        let mut problems = Vec::new();
+        let layer = parse_layer(&documented, Origin::Default, &mut problems);
+
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(Value::Table(layer).try_into::<Config>().unwrap(), Config::default());
+        for (key, doc) in KEY_DOCS {
+            assert!(documented.contains(&format!("# {}\n", doc)), "{} is not documented", key);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_variables_are_skipped_unless_they_are_ours() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let mut env = vars(&[("TMUX_SESSIONIBER_SETTINGS_ZOXIDE", "false")]);
+        env.push((OsString::from("BADVAR"), OsString::from_vec(b"\xff".to_vec())));
+        env.push((OsString::from_vec(b"BAD\xffNAME".to_vec()), OsString::from("x")));
+        env.push((OsString::from("TMUX_SESSIONIBER_SETTINGS_DEFAULT_VIEW"), OsString::from_vec(b"main\xff".to_vec())));
+        let loaded = Config::load_from(vec![], env, &[]);
+
+        assert!(!loaded.config.settings.zoxide);
+        assert_eq!(loaded.problems.len(), 1);
+        assert_eq!(loaded.problems[0].origin, Origin::Env("TMUX_SESSIONIBER_SETTINGS_DEFAULT_VIEW".to_string()));
+        assert_eq!(loaded.problems[0].message, "not valid UTF-8");
+    }
+
+    #[test]
+    fn missing_files_are_skipped() {
+        let files = vec![
+            (PathBuf::from("/etc/config.toml"), Err(io::Error::from(io::ErrorKind::NotFound))),
+            (PathBuf::from("/home/config.toml"), Err(io::Error::from(io::ErrorKind::PermissionDenied))),
+        ];
+        let loaded = Config::load_from(files, vec![], &[]);
+
+        assert_eq!(loaded.problems.len(), 1);
+        assert_eq!(loaded.problems[0].origin, Origin::File(PathBuf::from("/home/config.toml")));
+    }
+}
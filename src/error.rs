@@ -0,0 +1,54 @@
+use std::fmt;
+use std::io;
+
+use crate::config::ConfigProblem;
+use crate::tmux::TmuxError;
+
+/// Everything the app reports to the user: backend failures, and problems
+/// of its own that have nothing to do with tmux.
+#[derive(Debug)]
+pub enum AppError {
+    /// tmux or a template engine failed.
+    Tmux(TmuxError),
+    /// The config has problems; the affected values were skipped.
+    BadConfig(Vec<ConfigProblem>),
+    /// Sessions that could not be rebuilt by a restore, with why.
+    RestoreFailed(Vec<(String, TmuxError)>),
+    /// The request itself can't work, e.g. an invalid session name.
+    Invalid(String),
+}
+
+pub type Result<T> = std::result::Result<T, AppError>;
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Tmux(e) => write!(f, "{}", e),
+            AppError::BadConfig(problems) => {
+                write!(f, "problems in config:")?;
+                problems.iter().try_for_each(|p| write!(f, "\n{}", p))
+            }
+            AppError::RestoreFailed(failed) => {
+                write!(f, "could not restore:")?;
+                failed
+                    .iter()
+                    .try_for_each(|(name, e)| write!(f, "\n{}: {}", name, e))
+            }
+            AppError::Invalid(what) => write!(f, "{}", what),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<TmuxError> for AppError {
+    fn from(e: TmuxError) -> Self {
+        AppError::Tmux(e)
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(e: io::Error) -> Self {
+        AppError::Tmux(TmuxError::Io(e))
+    }
+}
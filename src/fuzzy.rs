@@ -0,0 +1,198 @@
+//! Subsequence fuzzy matching, in the spirit of fzf.
+//!
+//! Every query character must appear in the candidate, in order
+//! (case-insensitive). Among all ways of placing them, the best-scoring one
+//! wins: matches at word boundaries and runs of consecutive characters are
+//! rewarded, gaps between matched characters are penalized.
+
+const SCORE_MATCH: i64 = 16;
+const BONUS_BOUNDARY: i64 = 24;
+const BONUS_FIRST_CHAR: i64 = 8;
+const BONUS_CONSECUTIVE: i64 = 20;
+const PENALTY_GAP: i64 = 2;
+const PENALTY_LEADING: i64 = 1;
+
+/// A successful match: its score and the char indices of the matched characters.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    pub positions: Vec<usize>,
+}
+
+/// An item that matched a query, along with where it matched.
+#[derive(Clone, Debug)]
+pub struct Matched<T> {
+    pub item: T,
+    pub positions: Vec<usize>,
+}
+
+/// Score `candidate` against `query`, or `None` if it doesn't match.
+/// An empty query matches everything with a score of zero.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch::default());
+    }
+    let original: Vec<char> = candidate.chars().collect();
+    let lower: Vec<char> = original
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let (m, n) = (query.len(), lower.len());
+    if m > n {
+        return None;
+    }
+
+    // best[i][j]: best score with query[i] matched at candidate[j]
+    let mut best = vec![vec![None::<i64>; n]; m];
+    let mut from = vec![vec![0usize; n]; m];
+
+    for j in 0..n {
+        if lower[j] == query[0] {
+            let leading = (j as i64) * PENALTY_LEADING;
+            let first = if j == 0 { BONUS_FIRST_CHAR } else { 0 };
+            best[0][j] = Some(SCORE_MATCH + boundary_bonus(&original, j) + first - leading);
+        }
+    }
+    for i in 1..m {
+        for j in i..n {
+            if lower[j] != query[i] {
+                continue;
+            }
+            let bonus = SCORE_MATCH + boundary_bonus(&original, j);
+            for k in (i - 1)..j {
+                let Some(prev) = best[i - 1][k] else { continue };
+                let link = if k + 1 == j {
+                    BONUS_CONSECUTIVE
+                } else {
+                    -PENALTY_GAP * (j - k - 1) as i64
+                };
+                let score = prev + bonus + link;
+                if best[i][j].is_none_or(|b| score > b) {
+                    best[i][j] = Some(score);
+                    from[i][j] = k;
+                }
+            }
+        }
+    }
+
+    let (mut j, score) = best[m - 1]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, s)| s.map(|s| (j, s)))
+        .max_by_key(|&(j, s)| (s, std::cmp::Reverse(j)))?;
+
+    let mut positions = vec![0; m];
+    for i in (0..m).rev() {
+        positions[i] = j;
+        j = from[i][j];
+    }
+    Some(FuzzyMatch { score, positions })
+}
+
+/// Keep the items whose key matches `query`, best matches first.
+/// Ties keep their original order.
+pub fn rank<T: Clone>(query: &str, items: &[T], key: impl Fn(&T) -> &str) -> Vec<Matched<T>> {
+    let mut scored: Vec<(i64, Matched<T>)> = items
+        .iter()
+        .filter_map(|item| {
+            fuzzy_match(query, key(item)).map(|m| {
+                (
+                    m.score,
+                    Matched {
+                        item: item.clone(),
+                        positions: m.positions,
+                    },
+                )
+            })
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, m)| m).collect()
+}
+
+/// Bonus for matching at the start of a "word": after a separator or at a
+/// lower-to-upper case change (`client-api`, `clientApi`).
+fn boundary_bonus(chars: &[char], j: usize) -> i64 {
+    if j == 0 {
+        return BONUS_BOUNDARY;
+    }
+    let (prev, cur) = (chars[j - 1], chars[j]);
+    if matches!(prev, '-' | '_' | '.' | '/' | ' ' | ':')
+        || (prev.is_lowercase() && cur.is_uppercase())
+    {
+        BONUS_BOUNDARY
+    } else if prev.is_alphabetic() != cur.is_alphabetic() {
+        // e.g. the `2` in `v2`
+        BONUS_BOUNDARY / 2
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names<'a>(query: &str, items: &[&'a str]) -> Vec<&'a str> {
+        rank(query, items, |s| s)
+            .into_iter()
+            .map(|m| m.item)
+            .collect()
+    }
+
+    #[test]
+    fn boundaries_beat_scattered_matches() {
+        assert_eq!(
+            names("cav2", &["cache-validator2", "client-api-v2"]),
+            ["client-api-v2", "cache-validator2"]
+        );
+    }
+
+    #[test]
+    fn positions_are_the_best_placement() {
+        let m = fuzzy_match("cav2", "client-api-v2").unwrap();
+        assert_eq!(m.positions, [0, 7, 11, 12]);
+    }
+
+    #[test]
+    fn consecutive_run_beats_gaps() {
+        let run = fuzzy_match("api", "rapid").unwrap();
+        let gaps = fuzzy_match("api", "raspis").unwrap();
+        assert_eq!(run.positions, [1, 2, 3]);
+        assert!(run.score > gaps.score);
+    }
+
+    #[test]
+    fn matching_ignores_case() {
+        assert_eq!(fuzzy_match("CA", "clientApi").unwrap().positions, [0, 6]);
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert_eq!(
+            fuzzy_match("", "anything"),
+            Some(FuzzyMatch {
+                score: 0,
+                positions: vec![]
+            })
+        );
+        assert_eq!(names("", &["b", "a", "c"]), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn no_match() {
+        assert_eq!(fuzzy_match("xyz", "client-api"), None);
+        assert_eq!(fuzzy_match("ba", "ab"), None);
+        assert_eq!(fuzzy_match("long", "lon"), None);
+        assert!(names("zz", &["alpha", "beta"]).is_empty());
+    }
+
+    #[test]
+    fn ties_keep_their_order() {
+        assert_eq!(
+            names("web", &["web-2", "web-1", "notes"]),
+            ["web-2", "web-1"]
+        );
+    }
+}
@@ -1,8 +1,7 @@
 use crossterm::event::{Event, KeyCode};
-use crate::{
-    App, AppMode,
-    create_tmux_session, delete_tmux_session, list_tmux_sessions,
-};
+use crate::{App, AppMode};
+use crate::app::{session_name_fixes, session_name_problem, start_dir_problem, CreateField, NO_TEMPLATE};
+use crate::sources::Entry;
 use ratatui::widgets::{ListState};
 
 
@@ -16,6 +15,12 @@ pub enum EditorMode {
 
 pub fn handle_input(event: Event, app: &mut App) -> bool {
     if let Event::Key(key) = event {
+        // any key dismisses the error popup
+        if !app.errors.is_empty() {
+            app.errors.remove(0);
+            return false;
+        }
+        app.status = None;
         return match app.editor_mode {
             EditorMode::Normal => handle_normal_mode(key.code, app),
             EditorMode::Insert => handle_insert_mode(key.code, app),
@@ -37,20 +42,27 @@ fn handle_normal_mode(code: KeyCode, app: &mut App) -> bool {
             KeyCode::Char('k') | KeyCode::Up => move_up(&mut app.main_menu_selected, app.main_menu_items.len(), &mut app.main_list_state),
             KeyCode::Enter | KeyCode::Char('l') => match app.main_menu_selected {
                 0 => app.mode = AppMode::CreateSession,
-                1 => { app.sessions = list_tmux_sessions(); app.mode = AppMode::ListSessions; }
-                2 => return true,
+                1 => { app.refresh_sessions(); app.mode = AppMode::ListSessions; }
+                2 => app.save_state(),
+                3 => app.restore_state(),
+                4 => return true,
                 _ => {}
             },
             _ => {}
         },
 
         AppMode::ListSessions => match code {
-            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('h') => app.mode = AppMode::MainMenu,
+            // --print and --popup have no menu to go back to: leaving quits
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('h') if app.print_mode || app.popup_mode => return true,
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('h') => {
+                app.projects_only = false;
+                app.mode = AppMode::MainMenu;
+            }
             KeyCode::Char('i') => app.editor_mode = EditorMode::Insert,
             KeyCode::Char('v') => app.editor_mode = EditorMode::Visual,
             KeyCode::Char('j') | KeyCode::Down => {
                 // compute filtered first (immutable), then mutate selection safely
-                let filtered = app.filtered_sessions();
+                let filtered = app.filtered_entries();
                 let len = filtered.len();
                 move_down(&mut app.session_selected, len, &mut app.session_list_state);
 
@@ -60,29 +72,76 @@ fn handle_normal_mode(code: KeyCode, app: &mut App) -> bool {
             }
 
             KeyCode::Char('k') | KeyCode::Up => {
-                let filtered = app.filtered_sessions();
+                let filtered = app.filtered_entries();
                 let len = filtered.len();
                 move_up(&mut app.session_selected, len, &mut app.session_list_state);
             }
-            KeyCode::Enter | KeyCode::Char('l') => {
-                if let Some(s) = app.filtered_sessions().get(app.session_selected) {
-                    app.mode = AppMode::SessionActionMenu(s.clone());
+            KeyCode::Enter | KeyCode::Char('l') if app.print_mode => {
+                app.pick();
+                return true;
+            }
+            KeyCode::Enter | KeyCode::Char('l') if app.popup_mode => {
+                match app.selected_entry() {
+                    Some(Entry::Session(s)) => {
+                        app.attach(&s.name);
+                    }
+                    Some(Entry::Directory(dir)) => {
+                        app.open_directory(&dir);
+                    }
+                    None => return false,
                 }
+                // stay open to show what went wrong
+                return app.errors.is_empty();
             }
+            KeyCode::Enter | KeyCode::Char('l') => match app.selected_entry() {
+                Some(Entry::Session(s)) => app.mode = AppMode::SessionActionMenu(s.name),
+                Some(Entry::Directory(dir)) if app.open_directory(&dir) => return true,
+                _ => {}
+            },
             _ => {}
         },
 
         AppMode::CreateSession => match code {
-            KeyCode::Esc | KeyCode::Char('h') => app.mode = AppMode::MainMenu,
-            KeyCode::Char('i') => app.editor_mode = EditorMode::Insert,
-            KeyCode::Char('j') | KeyCode::Down => move_down(&mut app.template_selected, app.templates.len(), &mut app.template_list_state),
-            KeyCode::Char('k') | KeyCode::Up => move_up(&mut app.template_selected, app.templates.len(), &mut app.template_list_state),
+            KeyCode::Esc | KeyCode::Char('h') => {
+                app.reset_create_form();
+                app.mode = AppMode::MainMenu;
+            }
+            KeyCode::Char('i') => {
+                app.create_field = CreateField::Name;
+                app.editor_mode = EditorMode::Insert;
+            }
+            KeyCode::Char('d') => {
+                app.create_field = CreateField::Directory;
+                app.editor_mode = EditorMode::Insert;
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                let len = app.filtered_templates().len();
+                move_down(&mut app.template_selected, len, &mut app.template_list_state);
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                let len = app.filtered_templates().len();
+                move_up(&mut app.template_selected, len, &mut app.template_list_state);
+            }
             KeyCode::Enter | KeyCode::Char('l') => {
-                let name = app.input_buffer.clone();
-                let template = app.templates.get(app.template_selected).map(|s| s.as_str());
-                create_tmux_session(&name, template);
-                app.sessions = list_tmux_sessions();
-                app.input_buffer.clear();
+                let name = app.new_session_name().to_string();
+                if session_name_problem(&name, &app.sessions).is_some() {
+                    // reopen the name prompt, which lists the fixes
+                    app.create_field = CreateField::Name;
+                    app.editor_mode = EditorMode::Insert;
+                    return false;
+                }
+                let dir = app.start_dir();
+                if start_dir_problem(dir.as_deref()).is_some() {
+                    return false;
+                }
+                let templates = app.filtered_templates();
+                let template = templates.get(app.template_selected).filter(|t| t.name != NO_TEMPLATE);
+                // templates may attach, which suspends the TUI
+                app.needs_clear = template.is_some();
+                let result = app.backend.create_session(&name, template, dir.as_deref());
+                app.report(result);
+                app.refresh_sessions();
+                app.reset_create_form();
                 app.mode = AppMode::MainMenu;
             }
             _ => {}
@@ -90,18 +149,91 @@ fn handle_normal_mode(code: KeyCode, app: &mut App) -> bool {
 
         AppMode::SessionActionMenu(session) => match code {
             KeyCode::Esc | KeyCode::Char('h') => app.mode = AppMode::ListSessions,
-            KeyCode::Char('j') | KeyCode::Down => move_down(&mut app.session_action_selected, app.session_actions.len(), &mut app.session_list_state),
-            KeyCode::Char('k') | KeyCode::Up => move_up(&mut app.session_action_selected, app.session_actions.len(), &mut app.session_list_state),
-            // KeyCode::Enter => {
-            //     match app.session_action_selected {
-            //         0 => { Command::new("tmux").arg("attach").arg("-t").arg(session).spawn().ok(); }
-            //         1 => delete_tmux_session(session),
-            //         2 => { /* rename flow later */ }
-            //         _ => {}
-            //     }
-            //     app.sessions = list_tmux_sessions();
-            //     app.mode = AppMode::ListSessions;
-            // }
+            KeyCode::Char('j') | KeyCode::Down => move_down(&mut app.session_action_selected, app.session_actions.len(), &mut app.session_action_list_state),
+            KeyCode::Char('k') | KeyCode::Up => move_up(&mut app.session_action_selected, app.session_actions.len(), &mut app.session_action_list_state),
+            KeyCode::Enter | KeyCode::Char('l') => {
+                let session = session.clone();
+                match app.session_action_selected {
+                    0 => {
+                        if app.attach(&session) {
+                            return true;
+                        }
+                        app.refresh_sessions();
+                        app.mode = AppMode::ListSessions;
+                    }
+                    1 => {
+                        let result = app.backend.delete_session(&session);
+                        app.report(result);
+                        app.refresh_sessions();
+                        app.clamp_session_selection();
+                        app.mode = AppMode::ListSessions;
+                    }
+                    2 => {
+                        app.input_buffer = session.clone();
+                        app.editor_mode = EditorMode::Insert;
+                        app.mode = AppMode::RenameSession(session);
+                    }
+                    3 => app.open_windows(&session),
+                    4 => {
+                        app.save_as_template(&session);
+                        app.mode = AppMode::ListSessions;
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        },
+
+        AppMode::ListWindows(session) => match code {
+            KeyCode::Esc | KeyCode::Char('h') => app.mode = AppMode::SessionActionMenu(session.clone()),
+            KeyCode::Char('j') | KeyCode::Down => move_down(&mut app.window_selected, app.windows.len(), &mut app.window_list_state),
+            KeyCode::Char('k') | KeyCode::Up => move_up(&mut app.window_selected, app.windows.len(), &mut app.window_list_state),
+            KeyCode::Char('l') => {
+                if let Some(index) = app.windows.get(app.window_selected).map(|w| w.index) {
+                    let session = session.clone();
+                    app.open_panes(&session, index);
+                }
+            }
+            KeyCode::Enter => {
+                if let Some(target) = app.windows.get(app.window_selected).map(|w| w.target(session)) {
+                    let session = session.clone();
+                    if app.jump_to(&session, &target) {
+                        return true;
+                    }
+                    app.refresh_sessions();
+                    app.mode = AppMode::ListSessions;
+                }
+            }
+            _ => {}
+        },
+
+        AppMode::ListPanes(session, window) => match code {
+            KeyCode::Esc | KeyCode::Char('h') => {
+                let session = session.clone();
+                app.open_windows(&session);
+            }
+            KeyCode::Char('j') | KeyCode::Down => move_down(&mut app.pane_selected, app.panes.len(), &mut app.pane_list_state),
+            KeyCode::Char('k') | KeyCode::Up => move_up(&mut app.pane_selected, app.panes.len(), &mut app.pane_list_state),
+            KeyCode::Enter | KeyCode::Char('l') => {
+                if let Some(target) = app.panes.get(app.pane_selected).map(|p| p.target(session, *window)) {
+                    let session = session.clone();
+                    if app.jump_to(&session, &target) {
+                        return true;
+                    }
+                    app.refresh_sessions();
+                    app.mode = AppMode::ListSessions;
+                }
+            }
+            _ => {}
+        },
+
+        AppMode::RenameSession(session) => match code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                app.input_buffer.clear();
+                app.mode = AppMode::SessionActionMenu(session.clone());
+            }
+            KeyCode::Char('i') => app.editor_mode = EditorMode::Insert,
+            KeyCode::Enter => confirm_rename(app),
             _ => {}
         },
     }
@@ -114,12 +246,63 @@ fn handle_insert_mode(code: KeyCode, app: &mut App) -> bool {
     match app.mode {
         AppMode::ListSessions => match code {
             KeyCode::Esc => app.editor_mode = EditorMode::Normal,
-            KeyCode::Backspace => { app.search_query.pop(); }
-            KeyCode::Char(c) => app.search_query.push(c),
+            KeyCode::Backspace => {
+                app.search_query.pop();
+                app.session_selected = 0;
+            }
+            KeyCode::Char(c) => {
+                // the ranking changes with every keystroke, so start from the best match
+                app.search_query.push(c);
+                app.session_selected = 0;
+            }
             _ => {}
         },
-        AppMode::CreateSession => match code {
+        AppMode::CreateSession => match (app.create_field, code) {
+            (_, KeyCode::Esc) => app.editor_mode = EditorMode::Normal,
+            (CreateField::Name, KeyCode::Tab) => app.create_field = CreateField::Directory,
+            (CreateField::Name, KeyCode::Enter) => {
+                if session_name_problem(app.new_session_name(), &app.sessions).is_none() {
+                    app.editor_mode = EditorMode::Normal;
+                } else {
+                    return app.apply_name_fix();
+                }
+            }
+            (CreateField::Name, KeyCode::Down) => {
+                let len = session_name_fixes(app.new_session_name(), &app.sessions).len();
+                app.name_fix_selected = (app.name_fix_selected + 1).min(len.saturating_sub(1));
+            }
+            (CreateField::Name, KeyCode::Up) => app.name_fix_selected = app.name_fix_selected.saturating_sub(1),
+            (CreateField::Name, KeyCode::Backspace) => {
+                app.input_buffer.pop();
+                app.name_fix_selected = 0;
+            }
+            (CreateField::Name, KeyCode::Char(c)) => {
+                app.input_buffer.push(c);
+                app.name_fix_selected = 0;
+            }
+            (CreateField::Directory, KeyCode::BackTab) => app.create_field = CreateField::Name,
+            (CreateField::Directory, KeyCode::Tab) => app.complete_dir_buffer(),
+            (CreateField::Directory, KeyCode::Down) => {
+                let len = app.dir_completions().len();
+                move_down(&mut app.dir_selected, len, &mut app.dir_list_state);
+            }
+            (CreateField::Directory, KeyCode::Up) => {
+                let len = app.dir_completions().len();
+                move_up(&mut app.dir_selected, len, &mut app.dir_list_state);
+            }
+            (CreateField::Directory, KeyCode::Backspace) => {
+                app.dir_buffer.pop();
+                app.dir_selected = 0;
+            }
+            (CreateField::Directory, KeyCode::Char(c)) => {
+                app.dir_buffer.push(c);
+                app.dir_selected = 0;
+            }
+            _ => {}
+        },
+        AppMode::RenameSession(_) => match code {
             KeyCode::Esc => app.editor_mode = EditorMode::Normal,
+            KeyCode::Enter => confirm_rename(app),
             KeyCode::Backspace => { app.input_buffer.pop(); }
             KeyCode::Char(c) => app.input_buffer.push(c),
             _ => {}
@@ -129,19 +312,45 @@ fn handle_insert_mode(code: KeyCode, app: &mut App) -> bool {
     false
 }
 
+/// Rename the session being edited to `app.input_buffer` if the name is valid.
+/// An invalid name keeps the prompt open; the problem is shown in the popup.
+fn confirm_rename(app: &mut App) {
+    let AppMode::RenameSession(old) = &app.mode else { return };
+    let old = old.clone();
+    let new = app.input_buffer.trim().to_string();
+
+    if new != old {
+        if session_name_problem(&new, &app.sessions).is_some() {
+            return;
+        }
+        let result = app.backend.rename_session(&old, &new);
+        app.report(result);
+        app.refresh_sessions();
+    }
+    app.input_buffer.clear();
+    app.editor_mode = EditorMode::Normal;
+    app.mode = AppMode::ListSessions;
+}
+
 
 
 
 fn handle_visual_mode(code: KeyCode, app: &mut App) -> bool {
-    match &mut app.mode {
-        AppMode::ListSessions => match code {
+    if let AppMode::ListSessions = app.mode {
+        match code {
             KeyCode::Esc => {
+                app.marked.clear();
                 app.editor_mode = EditorMode::Normal;
             }
 
+            KeyCode::Enter if app.print_mode => {
+                app.pick();
+                return true;
+            }
+
             KeyCode::Char('j') | KeyCode::Down => {
                 // compute filtered first (immutable), then mutate selection safely
-                let filtered = app.filtered_sessions();
+                let filtered = app.filtered_entries();
                 let len = filtered.len();
                 move_down(&mut app.session_selected, len, &mut app.session_list_state);
 
@@ -151,39 +360,25 @@ fn handle_visual_mode(code: KeyCode, app: &mut App) -> bool {
             }
 
             KeyCode::Char('k') | KeyCode::Up => {
-                let filtered = app.filtered_sessions();
+                let filtered = app.filtered_entries();
                 let len = filtered.len();
                 move_up(&mut app.session_selected, len, &mut app.session_list_state);
             }
 
             KeyCode::Char('x') => {
                 // delete currently selected session in visual mode (example)
-                let filtered = app.filtered_sessions();
-                if !filtered.is_empty() {
-                    if let Some(name) = filtered.get(app.session_selected) {
-                        delete_tmux_session(name);
-                        app.sessions = list_tmux_sessions();
-                        // clamp selection
-                        let new_len = app.filtered_sessions().len();
-                        if app.session_selected >= new_len && new_len > 0 {
-                            app.session_selected = new_len - 1;
-                        }
-                        app.session_list_state.select(Some(app.session_selected));
-                    }
+                if let Some(Entry::Session(session)) = app.selected_entry() {
+                    let result = app.backend.delete_session(&session.name);
+                    app.report(result);
+                    app.refresh_sessions();
+                    app.clamp_session_selection();
                 }
             }
 
-            // you can add space to toggle selection, etc.
-            KeyCode::Char(' ') => {
-                // toggle selection in a visual-set (see suggestion below)
-                // let idx = app.session_selected;
-                // app.toggle_visual_selection(idx);
-            }
+            KeyCode::Char(' ') => app.toggle_mark(),
 
             _ => {}
-        },
-
-        _ => {}
+        }
     }
 
     false
@@ -269,7 +464,7 @@ fn handle_visual_mode(code: KeyCode, app: &mut App) -> bool {
 //     false
 // }
 
-pub fn move_up(selected: &mut usize, len: usize, state: &mut ListState) {
+pub fn move_up(selected: &mut usize, _len: usize, state: &mut ListState) {
     if *selected > 0 {
         *selected -= 1;
     }
@@ -291,3 +486,176 @@ pub fn move_down(selected: &mut usize, len: usize, state: &mut ListState) {
     }
     state.select(Some(*selected));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEvent, KeyModifiers};
+    use crate::error::AppError;
+    use crate::tmux::fake::{Call, FakeBackend};
+    use crate::tmux::{Pane, TmuxError, Window};
+
+    fn press(app: &mut App, keys: &[KeyCode]) -> bool {
+        keys.iter().fold(false, |_, code| handle_input(Event::Key(KeyEvent::new(*code, KeyModifiers::NONE)), app))
+    }
+
+    /// An app showing the action menu of the first session of `fake`.
+    fn action_menu(fake: FakeBackend) -> App {
+        let mut app = App::new(Box::new(fake));
+        press(&mut app, &[KeyCode::Down, KeyCode::Enter, KeyCode::Enter]);
+        assert!(matches!(&app.mode, AppMode::SessionActionMenu(s) if s == "alpha"));
+        app
+    }
+
+    #[test]
+    fn attach_from_action_menu() {
+        let fake = FakeBackend::new().with_sessions(&["alpha", "beta"]);
+        let calls = fake.calls();
+        let mut app = action_menu(fake);
+
+        assert!(!press(&mut app, &[KeyCode::Enter]));
+        assert_eq!(*calls.borrow(), vec![Call::Attach("alpha".to_string())]);
+        assert!(matches!(app.mode, AppMode::ListSessions));
+    }
+
+    #[test]
+    fn attach_inside_tmux_exits() {
+        let mut fake = FakeBackend::new().with_sessions(&["alpha"]);
+        fake.inside_tmux = true;
+        let mut app = action_menu(fake);
+
+        assert!(press(&mut app, &[KeyCode::Enter]));
+    }
+
+    #[test]
+    fn delete_from_action_menu() {
+        let fake = FakeBackend::new().with_sessions(&["alpha", "beta"]);
+        let calls = fake.calls();
+        let mut app = action_menu(fake);
+
+        press(&mut app, &[KeyCode::Down, KeyCode::Enter]);
+        assert_eq!(*calls.borrow(), vec![Call::Delete("alpha".to_string())]);
+        assert_eq!(app.sessions.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["beta"]);
+        assert!(matches!(app.mode, AppMode::ListSessions));
+    }
+
+    #[test]
+    fn rename_from_action_menu() {
+        let fake = FakeBackend::new().with_sessions(&["alpha", "beta"]);
+        let calls = fake.calls();
+        let mut app = action_menu(fake);
+
+        press(&mut app, &[KeyCode::Down, KeyCode::Down, KeyCode::Enter]);
+        assert_eq!(app.input_buffer, "alpha");
+        press(&mut app, &[KeyCode::Char('2'), KeyCode::Enter]);
+        assert_eq!(
+            *calls.borrow(),
+            vec![Call::Rename { old: "alpha".to_string(), new: "alpha2".to_string() }]
+        );
+        assert!(app.sessions.iter().any(|s| s.name == "alpha2"));
+    }
+
+    #[test]
+    fn rename_to_taken_name_keeps_prompt_open() {
+        let fake = FakeBackend::new().with_sessions(&["alpha", "beta"]);
+        let calls = fake.calls();
+        let mut app = action_menu(fake);
+
+        press(&mut app, &[KeyCode::Down, KeyCode::Down, KeyCode::Enter]);
+        app.input_buffer = "beta".to_string();
+        press(&mut app, &[KeyCode::Enter]);
+        assert!(calls.borrow().is_empty());
+        assert!(matches!(app.mode, AppMode::RenameSession(_)));
+    }
+
+    #[test]
+    fn failure_shows_error_popup_until_key_press() {
+        let mut fake = FakeBackend::new().with_sessions(&["alpha"]);
+        fake.fail_next = Some(TmuxError::NoServer);
+        let calls = fake.calls();
+        let mut app = action_menu(fake);
+
+        press(&mut app, &[KeyCode::Enter]);
+        assert!(matches!(app.errors[..], [AppError::Tmux(TmuxError::NoServer)]));
+        assert!(calls.borrow().is_empty());
+
+        // the next key only dismisses the popup
+        press(&mut app, &[KeyCode::Down]);
+        assert!(app.errors.is_empty());
+        assert_eq!(app.session_selected, 0);
+    }
+
+    #[test]
+    fn create_from_template() {
+        let fake = FakeBackend::new().with_templates(&["rust"]);
+        let calls = fake.calls();
+        let mut app = App::new(Box::new(fake));
+
+        press(&mut app, &[KeyCode::Enter, KeyCode::Char('i'), KeyCode::Char('w'), KeyCode::Esc]);
+        press(&mut app, &[KeyCode::Down, KeyCode::Enter]);
+        assert_eq!(
+            *calls.borrow(),
+            vec![Call::Create { name: "w".to_string(), template: Some("rust".to_string()), dir: None }]
+        );
+        assert!(matches!(app.mode, AppMode::MainMenu));
+    }
+
+    #[test]
+    fn name_fix_renames_the_typed_name() {
+        let fake = FakeBackend::new().with_sessions(&["api"]);
+        let calls = fake.calls();
+        let mut app = App::new(Box::new(fake));
+
+        press(&mut app, &[KeyCode::Enter, KeyCode::Char('i'), KeyCode::Char('a'), KeyCode::Char(':'), KeyCode::Char('b')]);
+        assert!(!press(&mut app, &[KeyCode::Enter]));
+        assert_eq!(app.input_buffer, "a_b");
+        assert!(calls.borrow().is_empty());
+    }
+
+    #[test]
+    fn name_fix_attaches_to_the_taken_name() {
+        let fake = FakeBackend::new().with_sessions(&["api"]);
+        let calls = fake.calls();
+        let mut app = App::new(Box::new(fake));
+
+        press(&mut app, &[KeyCode::Enter, KeyCode::Char('i'), KeyCode::Char('a'), KeyCode::Char('p'), KeyCode::Char('i')]);
+        press(&mut app, &[KeyCode::Down, KeyCode::Enter]);
+        assert_eq!(*calls.borrow(), vec![Call::Attach("api".to_string())]);
+        assert!(app.input_buffer.is_empty());
+    }
+
+    #[test]
+    fn jump_to_pane() {
+        let window = Window { index: 1, active: true, ..Default::default() };
+        let panes = vec![Pane { index: 1, ..Default::default() }, Pane { index: 2, ..Default::default() }];
+        let fake = FakeBackend::new()
+            .with_sessions(&["alpha"])
+            .with_windows("alpha", vec![window])
+            .with_panes("alpha:1", panes);
+        let calls = fake.calls();
+        let mut app = action_menu(fake);
+
+        press(&mut app, &[KeyCode::Down, KeyCode::Down, KeyCode::Down, KeyCode::Enter]);
+        assert!(matches!(app.mode, AppMode::ListWindows(_)));
+        press(&mut app, &[KeyCode::Char('l'), KeyCode::Down, KeyCode::Enter]);
+        assert_eq!(
+            *calls.borrow(),
+            vec![Call::Select("alpha:1.2".to_string()), Call::Attach("alpha".to_string())]
+        );
+    }
+
+    #[test]
+    fn errors_queue_up_instead_of_replacing_each_other() {
+        let mut fake = FakeBackend::new().with_sessions(&["alpha"]);
+        fake.fail_next = Some(TmuxError::NoServer);
+        let mut app = action_menu(fake);
+
+        press(&mut app, &[KeyCode::Enter]);
+        app.errors.push(AppError::Invalid("bad config".to_string()));
+        assert_eq!(app.errors.len(), 2);
+        press(&mut app, &[KeyCode::Esc]);
+        assert!(matches!(app.errors[..], [AppError::Invalid(_)]));
+        press(&mut app, &[KeyCode::Esc]);
+        assert!(app.errors.is_empty());
+    }
+}
@@ -3,17 +3,29 @@ mod input;
 mod theme;
 mod config;
 mod components;
+mod tmux;
+mod fuzzy;
+mod templates;
+mod state;
+mod resurrect;
+mod sources;
+mod cli;
+mod error;
 
 
-use app::{App, AppMode};
-use input::handle_input;
-use config::Config;
-use color_eyre::{Result};
-use theme::Theme;
-use components::{list_widget::styled_list, help_bar::{self, draw_help_bar}, popup::draw_popup};
+use app::{session_name_fixes, session_name_problem, start_dir_problem, App, AppMode, CreateField, View};
+use input::{handle_input, EditorMode};
+use cli::{Cli, Command};
+use clap::Parser;
+use config::{Config, Origin};
+use color_eyre::Result;
+use error::AppError;
+use tmux::ProcessBackend;
+use components::{list_widget::{styled_list, Marked}, help_bar::draw_help_bar, popup::{draw_popup, draw_error_popup}, preview::draw_preview, input_box::draw_input_box, dir_browser::draw_dir_browser};
 
 use crossterm::{
-    event::{self, Event, KeyCode},
+    cursor,
+    event,
     execute,
     terminal::{enable_raw_mode, disable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -21,112 +33,139 @@ use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout},
     Terminal,
-    widgets::{ ListState },
 };
 
-use std::process::{Command, Output};
-use std::default::Default;
+use std::fs::OpenOptions;
 use std::io::{self, Write};
+use std::process::ExitCode;
+use std::time::Duration;
 
+/// How often the pane preview is re-captured while idle.
+const PREVIEW_INTERVAL: Duration = Duration::from_millis(500);
 
-// Helper functions for tmux/tmuxifier
-fn list_tmux_sessions() -> Vec<String> {
-    
-
-let output = Command::new("tmux")
-    .arg("ls")
-    .output()
-    .unwrap();
 
-    if !output.status.success() {
-        return vec![];
+/// Report the outcome of a CLI subcommand on stderr and in the exit code.
+fn exit_code(result: error::Result<()>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("tmux-sessioniber: {}", e);
+            ExitCode::FAILURE
+        }
     }
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    stdout.lines().map(|l| l.split(':').next().unwrap_or("").to_string()).collect()
 }
 
-fn list_tmuxifier_templates() -> Vec<String> {
-    let output = Command::new("tmuxifier").arg("ls").output().unwrap();
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut v: Vec<String> = stdout.lines().map(|s| s.to_string()).collect();
-    v.insert(0, "No Template".to_string()); // Option to create without template
-    v
+/// Where the TUI draws: the terminal itself with `tty` (for `--print`),
+/// stdout otherwise.
+fn tui_output(tty: bool) -> io::Result<Box<dyn Write>> {
+    Ok(if tty {
+        Box::new(OpenOptions::new().write(true).open("/dev/tty")?)
+    } else {
+        Box::new(io::stdout())
+    })
 }
 
-fn create_tmux_session(name: &str, template: Option<&str>) {
-    disable_raw_mode().unwrap();
-    execute!(io::stdout(), LeaveAlternateScreen).unwrap();
-    match template {
-        Some(t) if t == "No Template" => {
-            Command::new("tmux")
-                .arg("new-session")
-                .arg("-d")
-                .arg("-s")
-                .arg(name)
-                .status()
-                .unwrap();
-        }
-        Some(t) => {
-            Command::new("tmuxifier")
-                .arg("load-session")
-                .arg(t)
-                .arg(name)
-                .status()
-                .unwrap();
-        }
-        None => {
-            // fallback: just make a new tmux session
-            Command::new("tmux")
-                .arg("new-session")
-                .arg("-d")
-                .arg("-s")
-                .arg(name)
-                .status()
-                .unwrap();
-        }    
-      }
-execute!(io::stdout(), EnterAlternateScreen).unwrap();
-    enable_raw_mode().unwrap();
-}
-
-fn delete_tmux_session(name: &str) {
-    Command::new("tmux").arg("kill-session").arg("-t").arg(name).status().unwrap();
+/// Undo raw mode and the alternate screen. Failures are ignored, as this
+/// also runs while `main` is already failing or panicking.
+fn restore_terminal(tty: bool) {
+    tmux::command::set_tui_active(false);
+    let _ = disable_raw_mode();
+    if let Ok(mut out) = tui_output(tty) {
+        let _ = execute!(out, LeaveAlternateScreen, cursor::Show);
+    }
 }
 
-fn rename_tmux_session(old: &str, new: &str) {
-    Command::new("tmux").arg("rename-session").arg("-t").arg(old).arg(new).status().unwrap();
+/// Restores the terminal when dropped, however the TUI loop is left.
+struct TerminalGuard {
+    tty: bool,
 }
 
-fn attach_tmux_session(name: &str) {
-    // Detach from TUI temporarily
-    disable_raw_mode().unwrap();
-    execute!(io::stdout(), LeaveAlternateScreen).unwrap();
-    Command::new("tmux").arg("attach-session").arg("-t").arg(name).status().unwrap();
-
-    // Re-enter TUI
-    execute!(io::stdout(), EnterAlternateScreen).unwrap();
-    enable_raw_mode().unwrap();
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal(self.tty);
+    }
 }
 
 // ------------------ MAIN ------------------
-fn main() -> Result<()> {
+fn main() -> Result<ExitCode> {
     color_eyre::install()?;
+    let cli = Cli::parse();
+    let loaded = Config::load(&cli.overrides());
+    // `config` commands must work even when the config is broken
+    if let Some(Command::Config { command }) = cli.command {
+        return Ok(exit_code(cli::run_config(command, &loaded)));
+    }
+    let config = loaded.config;
+    let theme = config.theme();
+    let mut tmux_backend = ProcessBackend::new(&config.settings.template_engine, &Config::layouts_dir());
+
+    match cli.command {
+        None | Some(Command::Tui) => {}
+        Some(command) => {
+            return Ok(exit_code(cli::run(command, &mut tmux_backend, &mut io::stdout())));
+        }
+    }
+
+    let view = match config.settings.default_view.parse::<View>() {
+        Ok(view) => view,
+        Err(e) => {
+            let origin = loaded.origins.get("settings.default_view").cloned().unwrap_or(Origin::Default);
+            eprintln!("tmux-sessioniber: default_view from {}: {}", origin, e);
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+
+    // with --print, stdout carries the selection, so draw on the terminal itself
+    let mut out = tui_output(cli.print)?;
+    let print = cli.print;
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // restore first, or the report is lost with the alternate screen
+        restore_terminal(print);
+        default_hook(info);
+    }));
     enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
+    let guard = TerminalGuard { tty: cli.print };
+    execute!(out, EnterAlternateScreen)?;
+    tmux::command::set_tui_active(true);
+    let backend = CrosstermBackend::new(out);
     let mut terminal = Terminal::new(backend)?;
-    let config = Config::load();
-    let theme = config.theme();
-    let mut app = App::new();
+    let mut app = App::new(Box::new(tmux_backend));
+    app.exit_after_switch = config.settings.exit_after_switch;
+    app.open_view(view);
+    if !loaded.problems.is_empty() {
+        app.errors.push(AppError::BadConfig(loaded.problems));
+    }
+    if cli.print || cli.popup {
+        app.print_mode = cli.print;
+        app.popup_mode = cli.popup;
+        app.mode = AppMode::ListSessions;
+    }
+    app.directories = sources::projects::scan(&config.projects);
+    if config.settings.zoxide {
+        app.directories.extend(sources::zoxide::query());
+    }
 
 
     loop {
+        if matches!(app.mode, AppMode::ListSessions) && !app.popup_mode {
+            app.refresh_preview();
+        }
+        if app.needs_clear {
+            terminal.clear()?;
+            app.needs_clear = false;
+        }
         terminal.draw(|f| {
             let size = f.area();
+            // popups are small: give the list every row but the help bar's
+            let constraints = if app.popup_mode {
+                [Constraint::Min(0), Constraint::Length(3)]
+            } else {
+                [Constraint::Percentage(85), Constraint::Percentage(15)]
+            };
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
-                .constraints([Constraint::Percentage(85), Constraint::Percentage(15)])
+                .constraints(constraints)
                 .split(size);
 
             match &app.mode {
@@ -139,16 +178,53 @@ fn main() -> Result<()> {
                     // let list = List::new(items)
                     //     .block(Block::default().title("Main Menu").borders(Borders::ALL))
                     //     .highlight_style(Style::default().bg(Color::Blue));
-                    let menu_items: Vec<String> = app.main_menu_items.iter().map(|s| s.to_string()).collect();
-                    styled_list(f, chunks[0], menu_items, &mut app.main_list_state, "Main Menu", &theme, app.main_menu_selected);
+                    styled_list(f, chunks[0], &app.main_menu_items, &mut app.main_list_state, "Main Menu", &theme, app.main_menu_selected);
+                    if let Some(status) = &app.status {
+                        draw_help_bar(f, chunks[1], status, &theme);
+                    }
                     // f.render_stateful_widget(list, chunks[0], &mut main_list_state);
                     // let help = Paragraph::new("Use Up/Down to move | Enter to select");
                     // f.render_widget(help, chunks[1]);
                 }
                 AppMode::CreateSession => {
-                    let templatess = app.filtered_templates();
-                    draw_popup(f, chunks[0], "Enter Session Name", &app.input_buffer, &theme);
-                    styled_list(f, chunks[0], templatess, &mut app.template_list_state, &format!("Session Name: {}", app.input_buffer), &theme, app.template_selected);
+                    let templatess = app.template_matches();
+                    let editing = app.editor_mode == EditorMode::Insert;
+                    let rows = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Length(3), Constraint::Min(0)])
+                        .split(chunks[0]);
+                    let start_dir = app.start_dir();
+                    let dir_problem = start_dir_problem(start_dir.as_deref());
+                    if editing && app.create_field == CreateField::Directory {
+                        draw_input_box(f, rows[0], "Session Name", &app.input_buffer, None, false, &theme);
+                        let completions = app.dir_completions();
+                        draw_dir_browser(f, rows[1], &app.dir_buffer, &completions, &mut app.dir_list_state, app.dir_selected, dir_problem.as_deref(), &theme);
+                        draw_help_bar(f, chunks[1], "Tab to complete | Up/Down to choose | Shift-Tab for name | Esc when done", &theme);
+                    } else {
+                        let name_cols = Layout::default()
+                            .direction(Direction::Horizontal)
+                            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                            .split(rows[0]);
+                        let name_focused = editing && app.create_field == CreateField::Name;
+                        draw_input_box(f, name_cols[0], "Session Name", &app.input_buffer, None, name_focused, &theme);
+                        let dir_label = if app.dir_buffer.is_empty() { "(current directory)" } else { &app.dir_buffer };
+                        draw_input_box(f, name_cols[1], "Directory", dir_label, dir_problem.as_deref(), false, &theme);
+                        styled_list(f, rows[1], &templatess, &mut app.template_list_state, "Templates", &theme, app.template_selected);
+                        if name_focused {
+                            let name = app.new_session_name();
+                            let mut text = app.input_buffer.clone();
+                            for (i, fix) in session_name_fixes(name, &app.sessions).iter().enumerate() {
+                                let marker = if i == app.name_fix_selected { ">" } else { " " };
+                                text.push_str(&format!("\n{} {}", marker, fix.label()));
+                            }
+                            let footer = session_name_problem(name, &app.sessions)
+                                .unwrap_or_else(|| "Enter when done | Tab for directory".to_string());
+                            draw_popup(f, chunks[0], "Session Name", &text, &footer, &theme);
+                            draw_help_bar(f, chunks[1], "Up/Down to choose a fix | Enter to apply it | Esc when done", &theme);
+                        } else {
+                            draw_help_bar(f, chunks[1], "i for name | d for directory | Enter to create | h to go back", &theme);
+                        }
+                    }
                     // let template_items: Vec<ListItem> = templatess
                     //     .iter()
                     //     .enumerate()
@@ -168,9 +244,30 @@ fn main() -> Result<()> {
                     // f.render_widget(help, chunks[1]);
                 }
                 AppMode::ListSessions => {
-                    let filtered = app.filtered_sessions();
-                    styled_list(f, chunks[0], filtered, &mut app.session_list_state, "Sessions", &theme, app.session_selected);
-                    draw_help_bar(f, chunks[1], &app.search_query, &theme);
+                    let filtered = app.entry_matches();
+                    // no preview in a popup
+                    let preview_width = if app.popup_mode { 0 } else { 60 };
+                    let columns = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints([Constraint::Percentage(100 - preview_width), Constraint::Percentage(preview_width)])
+                        .split(chunks[0]);
+                    let preview_title = filtered.get(app.session_selected).map(|m| m.item.name().to_string()).unwrap_or_default();
+                    if app.editor_mode == EditorMode::Visual {
+                        let marked: Vec<_> = filtered
+                            .into_iter()
+                            .map(|m| {
+                                let is_marked = app.marked.iter().any(|n| n == m.item.name());
+                                Marked::new(m, is_marked)
+                            })
+                            .collect();
+                        styled_list(f, columns[0], &marked, &mut app.session_list_state, "Sessions", &theme, app.session_selected);
+                    } else {
+                        styled_list(f, columns[0], &filtered, &mut app.session_list_state, "Sessions", &theme, app.session_selected);
+                    }
+                    if !app.popup_mode {
+                        draw_preview(f, columns[1], &preview_title, &app.preview, &theme);
+                    }
+                    draw_help_bar(f, chunks[1], app.status.as_deref().unwrap_or(&app.search_query), &theme);
                     // let items: Vec<ListItem> = filtered
                     //     .iter()
                     //     .enumerate()
@@ -190,9 +287,7 @@ fn main() -> Result<()> {
                     // f.render_widget(help, chunks[1]);
                 }
                 AppMode::SessionActionMenu(session) => {
-                    let actions: Vec<String> = app.session_actions.iter().map(|s| s.to_string()).collect()
-;
-                    styled_list(f, chunks[0], actions, &mut app.session_action_list_state, &format!("Session: {}", session), &theme, app.session_action_selected);
+                    styled_list(f, chunks[0], &app.session_actions, &mut app.session_action_list_state, &format!("Session: {}", session), &theme, app.session_action_selected);
                     // let items: Vec<ListItem> = actions.iter().map(|a| ListItem::new(a.to_string())).collect();
                     // let list = List::new(items)
                     //     .block(Block::default().title(format!("Session: {}", session)).borders(Borders::ALL))
@@ -201,8 +296,34 @@ fn main() -> Result<()> {
                     // let help = Paragraph::new("Use Up/Down | Enter to select | Esc to cancel");
                     // f.render_widget(help, chunks[1]);
                 }
+                AppMode::ListWindows(session) => {
+                    styled_list(f, chunks[0], &app.windows, &mut app.window_list_state, &format!("Windows: {}", session), &theme, app.window_selected);
+                    draw_help_bar(f, chunks[1], "Enter to jump | l for panes | h to go back", &theme);
+                }
+                AppMode::ListPanes(session, window) => {
+                    styled_list(f, chunks[0], &app.panes, &mut app.pane_list_state, &format!("Panes: {}:{}", session, window), &theme, app.pane_selected);
+                    draw_help_bar(f, chunks[1], "Enter to jump | h to go back", &theme);
+                }
+                AppMode::RenameSession(session) => {
+                    let filtered = app.filtered_entries();
+                    styled_list(f, chunks[0], &filtered, &mut app.session_list_state, "Sessions", &theme, app.session_selected);
+                    let new_name = app.input_buffer.trim();
+                    let footer = match session_name_problem(new_name, &app.sessions) {
+                        Some(problem) if new_name != session => problem,
+                        _ => "Enter to rename | Esc to cancel".to_string(),
+                    };
+                    draw_popup(f, chunks[0], &format!("Rename {}", session), &app.input_buffer, &footer, &theme);
+                }
+            }
+
+            if let Some(error) = app.errors.first() {
+                draw_error_popup(f, size, &error.to_string(), &theme);
             }
         })?;
+        // wake up regularly so the preview stays live
+        if !event::poll(PREVIEW_INTERVAL)? {
+            continue;
+        }
         let event = event::read()?;
         if handle_input(event, &mut app) {
             break;
@@ -295,9 +416,16 @@ fn main() -> Result<()> {
 //         }
     
 
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+    drop(guard);
 
-    Ok(())
+    if app.print_mode {
+        if app.picked.is_empty() {
+            // like fzf: cancelled
+            return Ok(ExitCode::from(130));
+        }
+        for name in &app.picked {
+            println!("{}", name);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
 }
@@ -0,0 +1,303 @@
+//! Import of tmux-resurrect save files.
+//!
+//! Each line of a save file is tab-separated and starts with its kind:
+//!
+//! ```text
+//! pane    <session> <window> <window_active> :<flags> <pane> [<title>] :<dir> <pane_active> <command> :<full command>
+//! window  <session> <window> :<name> <window_active> :<flags> <layout> [<automatic-rename>]
+//! state   <client session> <client last session>
+//! ```
+//!
+//! Older versions of tmux-resurrect don't save the pane title.
+//!
+//! Like tmux-resurrect itself, only the commands of a few well-known
+//! programs (`RESTORED_PROGRAMS`) are run again; every other pane gets a shell.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::state::SavedSession;
+use crate::templates::layout::{PaneLayout, SessionLayout, WindowLayout};
+use crate::tmux::Result;
+
+/// Programs whose saved command line is run again on import: tmux-resurrect's
+/// own default `@resurrect-processes`.
+pub const RESTORED_PROGRAMS: &[&str] = &[
+    "vi", "vim", "view", "nvim", "emacs", "man", "less", "more", "tail", "top", "htop", "irssi",
+    "weechat", "mutt",
+];
+
+/// The `last` symlink in tmux-resurrect's save directory.
+pub fn default_path() -> PathBuf {
+    if let Some(dir) = env::var_os("TMUX_RESURRECT_DIR") {
+        return PathBuf::from(dir).join("last");
+    }
+    let home = dirs::home_dir().unwrap_or_default();
+    let legacy = home.join(".tmux/resurrect/last");
+    if legacy.exists() {
+        return legacy;
+    }
+    dirs::data_dir()
+        .unwrap_or_else(|| home.join(".local/share"))
+        .join("tmux/resurrect/last")
+}
+
+pub fn load(path: &Path) -> Result<Vec<SavedSession>> {
+    Ok(parse(&fs::read_to_string(path)?))
+}
+
+struct PaneLine {
+    window: u32,
+    index: u32,
+    pane: PaneLayout,
+}
+
+/// Parse a save file into sessions, in the order they first appear.
+/// Lines that don't match the format are ignored.
+pub fn parse(content: &str) -> Vec<SavedSession> {
+    let mut order: Vec<String> = Vec::new();
+    let mut windows: BTreeMap<(String, u32), WindowLayout> = BTreeMap::new();
+    let mut panes: BTreeMap<String, Vec<PaneLine>> = BTreeMap::new();
+
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        match fields.first().copied() {
+            Some("pane") => {
+                let Some((session, pane)) = parse_pane(&fields) else {
+                    continue;
+                };
+                if !order.contains(&session) {
+                    order.push(session.clone());
+                }
+                panes.entry(session).or_default().push(pane);
+            }
+            Some("window") if fields.len() >= 7 => {
+                let Ok(index) = fields[2].parse() else {
+                    continue;
+                };
+                windows.insert(
+                    (fields[1].to_string(), index),
+                    WindowLayout {
+                        index: Some(index),
+                        name: Some(strip_colon(fields[3]).to_string()),
+                        focus: fields[4] == "1",
+                        layout: Some(fields[6].to_string()),
+                        ..Default::default()
+                    },
+                );
+            }
+            _ => {}
+        }
+    }
+
+    order
+        .into_iter()
+        .map(|name| {
+            let mut session_panes = panes.remove(&name).unwrap_or_default();
+            session_panes.sort_by_key(|p| (p.window, p.index));
+
+            let mut session_windows: Vec<WindowLayout> = Vec::new();
+            let mut current = None;
+            for line in session_panes {
+                if current != Some(line.window) {
+                    current = Some(line.window);
+                    let window = windows
+                        .remove(&(name.clone(), line.window))
+                        .unwrap_or_default();
+                    session_windows.push(window);
+                }
+                if let Some(window) = session_windows.last_mut() {
+                    window.panes.push(line.pane);
+                }
+            }
+
+            SavedSession {
+                name,
+                layout: SessionLayout {
+                    windows: session_windows,
+                    ..Default::default()
+                },
+            }
+        })
+        .collect()
+}
+
+fn parse_pane(fields: &[&str]) -> Option<(String, PaneLine)> {
+    // without the title column (older tmux-resurrect) everything after it shifts left
+    let offset = match fields.len() {
+        11.. => 0,
+        10 => 1,
+        _ => return None,
+    };
+    let session = fields[1].to_string();
+    let window = fields[2].parse().ok()?;
+    let index = fields[5].parse().ok()?;
+    let dir = strip_colon(fields[7 - offset]).replace("\\ ", " ");
+    let active = fields[8 - offset] == "1";
+    let command = fields[9 - offset];
+    let full_command = fields
+        .get(10 - offset)
+        .map(|c| strip_colon(c))
+        .unwrap_or("");
+
+    let replay = RESTORED_PROGRAMS.contains(&command) && !full_command.is_empty();
+    let pane = PaneLayout {
+        index: Some(index),
+        root: (!dir.is_empty()).then_some(dir),
+        command: replay.then(|| full_command.to_string()),
+        focus: active,
+        ..Default::default()
+    };
+    Some((
+        session,
+        PaneLine {
+            window,
+            index,
+            pane,
+        },
+    ))
+}
+
+fn strip_colon(field: &str) -> &str {
+    field.strip_prefix(':').unwrap_or(field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pane_line(fields: &[&str]) -> String {
+        std::iter::once("pane")
+            .chain(fields.iter().copied())
+            .collect::<Vec<_>>()
+            .join("\t")
+    }
+
+    #[test]
+    fn pane_line_with_title() {
+        let content = pane_line(&[
+            "work",
+            "1",
+            "1",
+            ":*",
+            "2",
+            "editor",
+            ":/home/u/my\\ code",
+            "1",
+            "vim",
+            ":vim notes.md",
+        ]);
+        let sessions = parse(&content);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].name, "work");
+        let pane = &sessions[0].layout.windows[0].panes[0];
+        assert_eq!(pane.index, Some(2));
+        assert_eq!(pane.root.as_deref(), Some("/home/u/my code"));
+        assert_eq!(pane.command.as_deref(), Some("vim notes.md"));
+        assert!(pane.focus);
+    }
+
+    #[test]
+    fn pane_line_without_title() {
+        let content = pane_line(&[
+            "work",
+            "1",
+            "1",
+            ":*",
+            "0",
+            ":/srv",
+            "0",
+            "less",
+            ":less app.log",
+        ]);
+        let pane = &parse(&content)[0].layout.windows[0].panes[0];
+        assert_eq!(pane.index, Some(0));
+        assert_eq!(pane.root.as_deref(), Some("/srv"));
+        assert_eq!(pane.command.as_deref(), Some("less app.log"));
+        assert!(!pane.focus);
+    }
+
+    #[test]
+    fn only_known_programs_are_replayed() {
+        let content = [
+            pane_line(&[
+                "work",
+                "0",
+                "1",
+                ":*",
+                "0",
+                "",
+                ":/",
+                "1",
+                "ssh",
+                ":ssh prod",
+            ]),
+            pane_line(&[
+                "work",
+                "0",
+                "1",
+                ":*",
+                "1",
+                "",
+                ":/",
+                "0",
+                "bash",
+                ":rm -rf build",
+            ]),
+            pane_line(&["work", "0", "1", ":*", "2", "", ":/", "0", "nvim", ":"]),
+        ]
+        .join("\n");
+        let panes = &parse(&content)[0].layout.windows[0].panes;
+        assert!(panes.iter().all(|p| p.command.is_none()));
+    }
+
+    #[test]
+    fn window_lines_name_and_lay_out_windows() {
+        let content = [
+            "window\twork\t3\t:logs\t0\t:-\teven-horizontal\t:".to_string(),
+            "window\twork\t1\t:editor\t1\t:*\tmain-vertical\t:".to_string(),
+            pane_line(&["work", "3", "0", ":-", "0", "", ":/", "0", "zsh", ":"]),
+            pane_line(&["work", "1", "1", ":*", "0", "", ":/", "1", "zsh", ":"]),
+        ]
+        .join("\n");
+        let windows = &parse(&content)[0].layout.windows;
+        let summary: Vec<_> = windows
+            .iter()
+            .map(|w| (w.index, w.name.as_deref(), w.layout.as_deref(), w.focus))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (Some(1), Some("editor"), Some("main-vertical"), true),
+                (Some(3), Some("logs"), Some("even-horizontal"), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn panes_are_grouped_by_session_in_order_of_appearance() {
+        let content = [
+            pane_line(&["b", "0", "1", ":*", "1", "", ":/b", "0", "zsh", ":"]),
+            pane_line(&["a", "0", "1", ":*", "0", "", ":/a", "1", "zsh", ":"]),
+            pane_line(&["b", "0", "1", ":*", "0", "", ":/b", "1", "zsh", ":"]),
+            pane_line(&["b", "1", "0", ":-", "0", "", ":/b", "1", "zsh", ":"]),
+            "state\tb\ta".to_string(),
+            "garbage".to_string(),
+        ]
+        .join("\n");
+        let sessions = parse(&content);
+        let names: Vec<&str> = sessions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["b", "a"]);
+
+        let b: Vec<Vec<Option<u32>>> = sessions[0]
+            .layout
+            .windows
+            .iter()
+            .map(|w| w.panes.iter().map(|p| p.index).collect())
+            .collect();
+        assert_eq!(b, [vec![Some(0), Some(1)], vec![Some(0)]]);
+        assert_eq!(sessions[1].layout.windows.len(), 1);
+    }
+}
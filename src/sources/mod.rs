@@ -0,0 +1,113 @@
+pub mod projects;
+pub mod zoxide;
+
+use crate::tmux::Session;
+
+/// A row of the session list: a running session, or a directory a new
+/// session can be started in.
+#[derive(Clone, Debug)]
+pub enum Entry {
+    Session(Session),
+    Directory(Directory),
+}
+
+/// A directory offered as a session source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Directory {
+    /// Session name derived from the folder name.
+    pub name: String,
+    pub path: String,
+    pub kind: DirectoryKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DirectoryKind {
+    /// Found by scanning the configured project roots.
+    Project,
+    /// Reported by `zoxide query`, with its frecency score.
+    Zoxide { score: f64 },
+}
+
+impl Entry {
+    pub fn name(&self) -> &str {
+        match self {
+            Entry::Session(s) => &s.name,
+            Entry::Directory(d) => &d.name,
+        }
+    }
+}
+
+impl Directory {
+    pub fn new(path: &str, kind: DirectoryKind) -> Self {
+        Directory {
+            name: session_name_for(path),
+            path: path.to_string(),
+            kind,
+        }
+    }
+}
+
+/// Session name for a directory: its folder name, minus the characters tmux
+/// doesn't allow in session names. `/` has no folder name and becomes `root`.
+pub fn session_name_for(path: &str) -> String {
+    let folder = path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(path);
+    if folder.is_empty() {
+        return "root".to_string();
+    }
+    sanitize_session_name(folder)
+}
+
+/// A session name for `path` that `taken` rejects: its folder name, else
+/// prefixed with the parent folder (`oss_api`), else with a numeric suffix.
+pub fn unique_session_name(path: &str, taken: impl Fn(&str) -> bool) -> String {
+    let name = session_name_for(path);
+    if !taken(&name) {
+        return name;
+    }
+    let trimmed = path.trim_end_matches('/');
+    let name = match trimmed.rsplit_once('/') {
+        Some((parent, _)) if !parent.is_empty() => format!("{}_{}", session_name_for(parent), name),
+        _ => name,
+    };
+    if !taken(&name) {
+        return name;
+    }
+    (2..)
+        .map(|i| format!("{}-{}", name, i))
+        .find(|n| !taken(n))
+        .unwrap_or(name)
+}
+
+/// `name` with the characters tmux doesn't allow in session names replaced by `_`.
+pub fn sanitize_session_name(name: &str) -> String {
+    name.replace(['.', ':'], "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_names_come_from_the_folder() {
+        assert_eq!(session_name_for("/home/u/code/api"), "api");
+        assert_eq!(session_name_for("/home/u/code/api/"), "api");
+        assert_eq!(session_name_for("/home/u/my.site"), "my_site");
+        assert_eq!(session_name_for("/"), "root");
+    }
+
+    #[test]
+    fn taken_names_get_the_parent_then_a_suffix() {
+        let taken = |names: &'static [&'static str]| move |n: &str| names.contains(&n);
+        assert_eq!(unique_session_name("/oss/api", taken(&[])), "api");
+        assert_eq!(unique_session_name("/oss/api", taken(&["api"])), "oss_api");
+        assert_eq!(
+            unique_session_name("/oss/api", taken(&["api", "oss_api"])),
+            "oss_api-2"
+        );
+        assert_eq!(unique_session_name("/api", taken(&["api"])), "api-2");
+    }
+}
@@ -0,0 +1,66 @@
+use std::fs;
+use std::path::Path;
+
+use glob::Pattern;
+
+use crate::config::ProjectsConfig;
+use crate::templates::layout::expand_home;
+
+use super::{Directory, DirectoryKind};
+
+/// Find project directories under the configured roots.
+///
+/// A directory containing one of `markers` (e.g. `.git`) is a project; the
+/// scan doesn't descend into it any further. Directories matching an
+/// `ignore` glob, by name or by full path, are skipped entirely.
+pub fn scan(config: &ProjectsConfig) -> Vec<Directory> {
+    let ignore: Vec<Pattern> = config
+        .ignore
+        .iter()
+        .filter_map(|g| Pattern::new(g).ok())
+        .collect();
+
+    let mut found = Vec::new();
+    for root in &config.roots {
+        let root = expand_home(root);
+        walk(Path::new(&root), 0, config, &ignore, &mut found);
+    }
+    found.sort_by(|a: &Directory, b| a.name.cmp(&b.name));
+    found
+}
+
+fn walk(
+    dir: &Path,
+    depth: usize,
+    config: &ProjectsConfig,
+    ignore: &[Pattern],
+    found: &mut Vec<Directory>,
+) {
+    if config.markers.iter().any(|m| dir.join(m).exists()) {
+        found.push(Directory::new(
+            &dir.display().to_string(),
+            DirectoryKind::Project,
+        ));
+        return;
+    }
+    if depth >= config.max_depth {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if ignore
+            .iter()
+            .any(|p| p.matches(&name) || p.matches_path(&path))
+        {
+            continue;
+        }
+        walk(&path, depth + 1, config, ignore, found);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::tmux::command::run;
+
+use super::{Directory, DirectoryKind};
+
+/// Directories known to zoxide, highest frecency first.
+///
+/// Returns nothing when zoxide isn't installed or fails, so the list just
+/// shows the other sources.
+pub fn query() -> Vec<Directory> {
+    query_with("zoxide")
+}
+
+/// `query`, running `program` as zoxide.
+fn query_with(program: &str) -> Vec<Directory> {
+    let Ok(output) = run(program, &["query", "--list", "--score"]) else {
+        return vec![];
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_line)
+        .collect()
+}
+
+/// Parse a `zoxide query -l -s` line: the score, then the path.
+fn parse_line(line: &str) -> Option<Directory> {
+    let (score, path) = line.trim_start().split_once(' ')?;
+    let score = score.parse().ok()?;
+    let path = path.trim_start();
+    (!path.is_empty()).then(|| Directory::new(path, DirectoryKind::Zoxide { score }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn parses_score_and_path() {
+        let dir = parse_line("  12.5 /home/u/my code").unwrap();
+        assert_eq!(dir.path, "/home/u/my code");
+        assert_eq!(dir.name, "my code");
+        assert_eq!(dir.kind, DirectoryKind::Zoxide { score: 12.5 });
+        assert_eq!(parse_line("4 /").unwrap().name, "root");
+        assert_eq!(parse_line("high /tmp"), None);
+        assert_eq!(parse_line("3.0 "), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reads_the_zoxide_list() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = env::temp_dir().join(format!("sessioniber-zoxide-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let stub = dir.join("zoxide");
+        fs::write(
+            &stub,
+            "#!/bin/sh\nprintf '  40.0 /home/u/api\\n   2.5 /srv/web\\n'\n",
+        )
+        .unwrap();
+        fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let found = query_with(stub.to_str().unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+
+        let paths: Vec<&str> = found.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(paths, ["/home/u/api", "/srv/web"]);
+        assert_eq!(found[1].kind, DirectoryKind::Zoxide { score: 2.5 });
+    }
+
+    #[test]
+    fn missing_zoxide_finds_nothing() {
+        let missing = env::temp_dir().join("sessioniber-no-such-zoxide");
+        assert!(query_with(missing.to_str().unwrap()).is_empty());
+    }
+}
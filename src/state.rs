@@ -0,0 +1,177 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::templates::layout::SessionLayout;
+use crate::templates::snapshot::snapshot;
+use crate::tmux::{Result, TmuxBackend, TmuxError};
+
+/// Bumped whenever the file format changes incompatibly.
+pub const STATE_VERSION: u32 = 1;
+
+/// Every session of a tmux server, as written to `state.json`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServerState {
+    pub version: u32,
+    /// Unix timestamp of the save.
+    pub saved_at: u64,
+    pub sessions: Vec<SavedSession>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedSession {
+    pub name: String,
+    #[serde(flatten)]
+    pub layout: SessionLayout,
+}
+
+/// What `restore` did with each saved session.
+#[derive(Debug, Default)]
+pub struct RestoreReport {
+    pub restored: Vec<String>,
+    /// Already running, left untouched.
+    pub skipped: Vec<String>,
+    /// Could not be built; the other sessions were still restored.
+    pub failed: Vec<(String, TmuxError)>,
+}
+
+/// `<data dir>/tmux-sessioniber/state.json`.
+pub fn default_path() -> PathBuf {
+    let dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from("."));
+    dir.join("tmux-sessioniber/state.json")
+}
+
+/// Snapshot every running session.
+pub fn capture(backend: &dyn TmuxBackend) -> Result<ServerState> {
+    let sessions = backend
+        .list_sessions()?
+        .into_iter()
+        .map(|s| {
+            Ok(SavedSession {
+                layout: snapshot(backend, &s.name)?,
+                name: s.name,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let saved_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    Ok(ServerState {
+        version: STATE_VERSION,
+        saved_at,
+        sessions,
+    })
+}
+
+/// Write the state of the server to `path`. Returns the number of sessions saved.
+pub fn save(backend: &dyn TmuxBackend, path: &Path) -> Result<usize> {
+    let state = capture(backend)?;
+    let json = serde_json::to_string_pretty(&state).map_err(|e| bad_state(path, e.to_string()))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, json)?;
+    Ok(state.sessions.len())
+}
+
+pub fn load(path: &Path) -> Result<ServerState> {
+    let content = fs::read_to_string(path)?;
+    let state: ServerState =
+        serde_json::from_str(&content).map_err(|e| bad_state(path, e.to_string()))?;
+    if state.version != STATE_VERSION {
+        return Err(bad_state(
+            path,
+            format!(
+                "unsupported version {} (expected {})",
+                state.version, STATE_VERSION
+            ),
+        ));
+    }
+    Ok(state)
+}
+
+/// Rebuild the sessions saved in `path`, skipping those that already exist.
+pub fn restore(backend: &mut dyn TmuxBackend, path: &Path) -> Result<RestoreReport> {
+    restore_sessions(backend, load(path)?.sessions, false)
+}
+
+/// Build each of `sessions` that isn't running yet. With `dry_run`, only
+/// report what would be created. A session that fails to build is recorded
+/// in the report and doesn't stop the others.
+pub fn restore_sessions(
+    backend: &mut dyn TmuxBackend,
+    sessions: Vec<SavedSession>,
+    dry_run: bool,
+) -> Result<RestoreReport> {
+    let running: Vec<String> = backend
+        .list_sessions()?
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+
+    let mut report = RestoreReport::default();
+    for session in sessions {
+        if running.contains(&session.name) {
+            report.skipped.push(session.name);
+            continue;
+        }
+        if !dry_run && let Err(e) = backend.build_layout(&session.name, &session.layout) {
+            report.failed.push((session.name, e));
+            continue;
+        }
+        report.restored.push(session.name);
+    }
+    Ok(report)
+}
+
+fn bad_state(path: &Path, message: String) -> TmuxError {
+    TmuxError::BadStateFile {
+        path: path.display().to_string(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tmux::fake::{Call, FakeBackend};
+
+    fn saved(name: &str) -> SavedSession {
+        SavedSession {
+            name: name.to_string(),
+            layout: SessionLayout::default(),
+        }
+    }
+
+    #[test]
+    fn restore_keeps_going_after_a_failure() {
+        let mut fake = FakeBackend::new().with_sessions(&["running"]);
+        fake.fail_next = Some(TmuxError::NoServer);
+        let calls = fake.calls();
+
+        let sessions = vec![saved("broken"), saved("running"), saved("fresh")];
+        let report = restore_sessions(&mut fake, sessions, false).unwrap();
+        assert_eq!(report.restored, ["fresh"]);
+        assert_eq!(report.skipped, ["running"]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, "broken");
+        assert_eq!(
+            *calls.borrow(),
+            vec![Call::BuildLayout("fresh".to_string())]
+        );
+    }
+
+    #[test]
+    fn dry_run_builds_nothing() {
+        let mut fake = FakeBackend::new();
+        let calls = fake.calls();
+
+        let report = restore_sessions(&mut fake, vec![saved("a"), saved("b")], true).unwrap();
+        assert_eq!(report.restored, ["a", "b"]);
+        assert!(calls.borrow().is_empty());
+    }
+}
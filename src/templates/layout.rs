@@ -0,0 +1,424 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tmux::command::{run, run_tmux_output};
+use crate::tmux::{Result, TmuxError};
+
+/// The native session layout format, stored as `layouts/<name>.toml`:
+///
+/// ```toml
+/// root = "~/code/api"
+///
+/// [env]
+/// RUST_LOG = "debug"
+///
+/// [[windows]]
+/// name = "editor"
+/// focus = true
+///
+///   [[windows.panes]]
+///   commands = ["nvim"]
+///   focus = true
+///
+///   [[windows.panes]]
+///   root = "tests"
+///   split = "right"
+///   size = "30%"
+///   command = "cargo watch -x test"
+/// ```
+///
+/// Relative `root`s resolve against the enclosing window's, then the session's.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionLayout {
+    /// Starting directory for every window and pane.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    /// Environment set on the session.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub windows: Vec<WindowLayout>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WindowLayout {
+    /// tmux window index in the snapshotted session. Windows are always
+    /// built in list order; this is only used to target them afterwards.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    /// A tmux layout name (`tiled`, `main-vertical`, ...) or a full layout string,
+    /// applied after the panes are split, so it overrides their `size`s.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout: Option<String>,
+    /// Select this window once the session is built.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub focus: bool,
+    /// Startup command for a window without `panes`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub panes: Vec<PaneLayout>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PaneLayout {
+    /// tmux pane index in the snapshotted window, like `WindowLayout::index`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    /// Where this pane goes relative to the previous one. Ignored for the first pane.
+    #[serde(default, skip_serializing_if = "Split::is_default")]
+    pub split: Split,
+    /// Size passed to `split-window -l`, e.g. `30%` or `20`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<String>,
+    /// Shorthand for a single entry in `commands`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Typed into the pane, in order, once it is created.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Select this pane in its window.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub focus: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Split {
+    /// Side by side (`split-window -h`).
+    #[default]
+    Right,
+    /// Stacked (`split-window -v`).
+    Below,
+}
+
+impl Split {
+    fn flag(self) -> &'static str {
+        match self {
+            Split::Right => "-h",
+            Split::Below => "-v",
+        }
+    }
+
+    fn is_default(&self) -> bool {
+        *self == Split::default()
+    }
+}
+
+fn is_false(b: &bool) -> bool {
+    !b
+}
+
+impl PaneLayout {
+    fn all_commands(&self) -> impl Iterator<Item = &String> {
+        self.command.iter().chain(self.commands.iter())
+    }
+}
+
+impl WindowLayout {
+    /// The panes to create; a window without any is a single pane running `command`.
+    fn pane_list(&self) -> Vec<PaneLayout> {
+        if self.panes.is_empty() {
+            vec![PaneLayout {
+                command: self.command.clone(),
+                ..Default::default()
+            }]
+        } else {
+            self.panes.clone()
+        }
+    }
+}
+
+impl SessionLayout {
+    /// Every command typed into a pane when the session is built, in order.
+    pub fn commands(&self) -> Vec<&str> {
+        self.windows
+            .iter()
+            .flat_map(|w| -> Vec<&String> {
+                if w.panes.is_empty() {
+                    w.command.iter().collect()
+                } else {
+                    w.panes.iter().flat_map(PaneLayout::all_commands).collect()
+                }
+            })
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Create the detached session `session` with tmux commands only. If any
+    /// step fails, the half-built session is killed again.
+    pub fn build(&self, session: &str) -> Result<()> {
+        let mut created = false;
+        let result = self.create(session, &mut created);
+        if result.is_err() && created {
+            let _ = run("tmux", &["kill-session", "-t", &format!("={}", session)]);
+        }
+        result
+    }
+
+    /// The steps of `build`; sets `created` once the session exists.
+    fn create(&self, session: &str, created: &mut bool) -> Result<()> {
+        let session_root = self.root.as_deref().map(expand_home);
+        let default_window = [WindowLayout::default()];
+        let windows = if self.windows.is_empty() {
+            &default_window[..]
+        } else {
+            &self.windows[..]
+        };
+
+        let mut focus_window = None;
+        for (i, window) in windows.iter().enumerate() {
+            let window_root = resolve(session_root.as_deref(), window.root.as_deref());
+            let panes = window.pane_list();
+
+            let first_root = resolve(window_root.as_deref(), panes[0].root.as_deref());
+            let mut args: Vec<String> = if i == 0 {
+                strings(&["new-session", "-d", "-s", session])
+            } else {
+                strings(&["new-window", "-t", &format!("{}:", session)])
+            };
+            // print the new ids so later commands can target them
+            args.extend(strings(&["-P", "-F", "#{window_id}\t#{pane_id}"]));
+            if let Some(name) = &window.name {
+                args.extend(strings(&["-n", name]));
+            }
+            if let Some(root) = &first_root {
+                args.extend(strings(&["-c", root]));
+            }
+            if i == 0 {
+                // `new-session -e` sets the session environment, so the
+                // first pane's own variables are applied by respawning it
+                args.extend(env_args(&self.env));
+            } else {
+                args.extend(env_args(&panes[0].env));
+            }
+            let output = run_owned(&args)?;
+            *created = true;
+            let Some((window_id, first_pane)) = output.split_once('\t') else {
+                return Err(TmuxError::CommandFailed {
+                    command: format!("tmux {}", args.join(" ")),
+                    stderr: format!("unexpected output `{}`", output),
+                });
+            };
+            let (window_id, first_pane) = (window_id.to_string(), first_pane.to_string());
+            if i == 0 && !panes[0].env.is_empty() {
+                let mut args = strings(&["respawn-pane", "-k", "-t", &first_pane]);
+                if let Some(root) = &first_root {
+                    args.extend(strings(&["-c", root]));
+                }
+                args.extend(env_args(&panes[0].env));
+                run_owned(&args)?;
+            }
+
+            let mut pane_ids = vec![first_pane];
+            for pane in &panes[1..] {
+                let root = resolve(window_root.as_deref(), pane.root.as_deref());
+                let previous = pane_ids.last().cloned().unwrap_or_default();
+                let mut args = strings(&[
+                    "split-window",
+                    pane.split.flag(),
+                    "-t",
+                    &previous,
+                    "-P",
+                    "-F",
+                    "#{pane_id}",
+                ]);
+                if let Some(size) = &pane.size {
+                    args.extend(strings(&["-l", size]));
+                }
+                if let Some(root) = &root {
+                    args.extend(strings(&["-c", root]));
+                }
+                args.extend(env_args(&pane.env));
+                pane_ids.push(run_owned(&args)?);
+            }
+
+            if let Some(layout) = &window.layout {
+                run("tmux", &["select-layout", "-t", &window_id, layout])?;
+            }
+            for (pane, id) in panes.iter().zip(&pane_ids) {
+                for command in pane.all_commands() {
+                    run("tmux", &["send-keys", "-t", id, command, "Enter"])?;
+                }
+            }
+            if let Some((_, id)) = panes.iter().zip(&pane_ids).find(|(p, _)| p.focus) {
+                run("tmux", &["select-pane", "-t", id])?;
+            }
+            if window.focus || focus_window.is_none() {
+                focus_window = Some(window_id);
+            }
+        }
+
+        if let Some(window_id) = focus_window {
+            run("tmux", &["select-window", "-t", &window_id])?;
+        }
+        Ok(())
+    }
+}
+
+/// Run `tmux` with owned args and return its trimmed stdout.
+fn run_owned(args: &[String]) -> Result<String> {
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let output = run_tmux_output(&args)?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn strings(args: &[&str]) -> Vec<String> {
+    args.iter().map(|s| s.to_string()).collect()
+}
+
+fn env_args(env: &BTreeMap<String, String>) -> Vec<String> {
+    env.iter()
+        .flat_map(|(k, v)| ["-e".to_string(), format!("{}={}", k, v)])
+        .collect()
+}
+
+/// `path` relative to `base` (when relative), with `~` expanded.
+fn resolve(base: Option<&str>, path: Option<&str>) -> Option<String> {
+    match (base, path.map(expand_home)) {
+        (_, Some(p)) if Path::new(&p).is_absolute() => Some(p),
+        (Some(base), Some(p)) => Some(Path::new(base).join(p).display().to_string()),
+        (None, Some(p)) => Some(p),
+        (base, None) => base.map(|b| b.to_string()),
+    }
+}
+
+/// Expand a leading `~` or `~/` to the home directory. Other users' homes
+/// (`~name`) are left alone.
+pub fn expand_home(path: &str) -> String {
+    match (home_relative(path.strip_prefix('~')), dirs::home_dir()) {
+        (Some(rest), Some(home)) => format!("{}{}", home.display(), rest),
+        _ => path.to_string(),
+    }
+}
+
+/// Replace the home directory prefix with `~`.
+pub fn shorten_home(path: &str) -> String {
+    match dirs::home_dir()
+        .and_then(|h| home_relative(path.strip_prefix(h.to_str()?)).map(|rest| rest.to_string()))
+    {
+        Some(rest) => format!("~{}", rest),
+        None => path.to_string(),
+    }
+}
+
+/// `rest` if it is empty or starts a new path component.
+fn home_relative(rest: Option<&str>) -> Option<&str> {
+    rest.filter(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn home() -> String {
+        dirs::home_dir().unwrap().display().to_string()
+    }
+
+    #[test]
+    fn parses_the_documented_format() {
+        let layout: SessionLayout = toml::from_str(
+            r#"
+            root = "~/code/api"
+
+            [env]
+            RUST_LOG = "debug"
+
+            [[windows]]
+            name = "editor"
+            focus = true
+
+              [[windows.panes]]
+              commands = ["nvim"]
+              focus = true
+
+              [[windows.panes]]
+              root = "tests"
+              split = "below"
+              size = "30%"
+              command = "cargo watch -x test"
+
+            [[windows]]
+            command = "lazygit"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(layout.root.as_deref(), Some("~/code/api"));
+        assert_eq!(
+            layout.env.get("RUST_LOG").map(String::as_str),
+            Some("debug")
+        );
+        let editor = &layout.windows[0];
+        assert_eq!(editor.name.as_deref(), Some("editor"));
+        assert!(editor.focus);
+        assert_eq!(editor.panes[0].commands, ["nvim"]);
+        assert_eq!(editor.panes[0].split, Split::Right);
+        assert_eq!(editor.panes[1].split, Split::Below);
+        assert_eq!(editor.panes[1].size.as_deref(), Some("30%"));
+        assert_eq!(
+            layout.windows[1].pane_list()[0].command.as_deref(),
+            Some("lazygit")
+        );
+        assert_eq!(
+            layout.commands(),
+            ["nvim", "cargo watch -x test", "lazygit"]
+        );
+    }
+
+    #[test]
+    fn unknown_split_is_rejected() {
+        let result =
+            toml::from_str::<SessionLayout>("[[windows]]\n[[windows.panes]]\nsplit = \"left\"\n");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn only_own_home_is_expanded() {
+        assert_eq!(expand_home("~"), home());
+        assert_eq!(expand_home("~/code"), format!("{}/code", home()));
+        assert_eq!(expand_home("~foo/code"), "~foo/code");
+        assert_eq!(expand_home("/srv/~"), "/srv/~");
+    }
+
+    #[test]
+    fn home_is_shortened_at_a_component_boundary() {
+        assert_eq!(shorten_home(&format!("{}/code", home())), "~/code");
+        assert_eq!(
+            shorten_home(&format!("{}2/code", home())),
+            format!("{}2/code", home())
+        );
+    }
+
+    #[test]
+    fn roots_resolve_against_their_parent() {
+        assert_eq!(
+            resolve(Some("/code/api"), Some("tests")).as_deref(),
+            Some("/code/api/tests")
+        );
+        assert_eq!(
+            resolve(Some("/code/api"), Some("/tmp")).as_deref(),
+            Some("/tmp")
+        );
+        assert_eq!(
+            resolve(Some("/code/api"), Some("~/notes")),
+            Some(format!("{}/notes", home()))
+        );
+        assert_eq!(
+            resolve(Some("/code/api"), None).as_deref(),
+            Some("/code/api")
+        );
+        assert_eq!(resolve(None, Some("tests")).as_deref(), Some("tests"));
+        assert_eq!(resolve(None, None), None);
+    }
+}
@@ -0,0 +1,83 @@
+pub mod layout;
+pub mod native;
+pub mod snapshot;
+pub mod tmuxifier;
+pub mod tmuxinator;
+pub mod tmuxp;
+
+use std::path::Path;
+
+use crate::tmux::{Result, TmuxError};
+
+use layout::SessionLayout;
+
+pub use native::NativeEngine;
+pub use tmuxifier::Tmuxifier;
+pub use tmuxinator::Tmuxinator;
+pub use tmuxp::Tmuxp;
+
+/// A session template offered by one of the configured engines.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Template {
+    pub name: String,
+    /// `TemplateEngine::name` of the engine that provides it.
+    pub engine: String,
+}
+
+impl Template {
+    pub fn new(engine: &str, name: &str) -> Self {
+        Template {
+            name: name.to_string(),
+            engine: engine.to_string(),
+        }
+    }
+}
+
+/// A tool that knows how to list session templates and start sessions from them.
+pub trait TemplateEngine {
+    /// Name used in `Settings.template_engine`, e.g. `tmuxifier`.
+    fn name(&self) -> &'static str;
+    fn list_templates(&self) -> Result<Vec<String>>;
+    /// Start a session called `session` from `template` in `dir`. The native
+    /// engine uses `dir` as the layout's root; the external tools are run in
+    /// it, so it only applies where their template sets no root of its own.
+    fn launch(&self, template: &str, session: &str, dir: Option<&str>) -> Result<()>;
+    /// Store `layout` as a new template called `name`.
+    fn save(&self, name: &str, _layout: &SessionLayout) -> Result<()> {
+        Err(TmuxError::Unsupported(format!(
+            "{} can't save template `{}`",
+            self.name(),
+            name
+        )))
+    }
+}
+
+/// Build the engines named in `Settings.template_engine`.
+///
+/// The setting is a comma-separated list (`"tmuxifier, tmuxp"`); `"all"`
+/// enables every engine.
+pub fn from_setting(setting: &str, layouts_dir: &Path) -> Result<Vec<Box<dyn TemplateEngine>>> {
+    let names: Vec<&str> = setting
+        .split(',')
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .collect();
+    let names = if names == ["all"] {
+        vec!["native", "tmuxifier", "tmuxinator", "tmuxp"]
+    } else {
+        names
+    };
+
+    names
+        .into_iter()
+        .map(|name| -> Result<Box<dyn TemplateEngine>> {
+            match name {
+                "native" => Ok(Box::new(NativeEngine::new(layouts_dir))),
+                "tmuxifier" => Ok(Box::new(Tmuxifier)),
+                "tmuxinator" => Ok(Box::new(Tmuxinator)),
+                "tmuxp" => Ok(Box::new(Tmuxp)),
+                other => Err(TmuxError::UnknownEngine(other.to_string())),
+            }
+        })
+        .collect()
+}
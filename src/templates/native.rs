@@ -0,0 +1,73 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::tmux::{Result, TmuxError};
+
+use super::TemplateEngine;
+use super::layout::SessionLayout;
+
+/// `SessionLayout` files stored as `*.toml` in the `layouts` directory next to
+/// `config.toml`, built with plain tmux commands.
+pub struct NativeEngine {
+    dir: PathBuf,
+}
+
+impl NativeEngine {
+    pub fn new(dir: &Path) -> Self {
+        NativeEngine {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    fn load(&self, template: &str) -> Result<SessionLayout> {
+        let path = self.dir.join(format!("{}.toml", template));
+        let content = fs::read_to_string(&path)?;
+        toml::from_str(&content).map_err(|e| TmuxError::BadTemplate {
+            path: path.display().to_string(),
+            message: e.message().to_string(),
+        })
+    }
+}
+
+impl TemplateEngine for NativeEngine {
+    fn name(&self) -> &'static str {
+        "native"
+    }
+
+    fn list_templates(&self) -> Result<Vec<String>> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            // no layouts directory yet
+            return Ok(vec![]);
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+            .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().into_owned()))
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    fn launch(&self, template: &str, session: &str, dir: Option<&str>) -> Result<()> {
+        let mut layout = self.load(template)?;
+        if let Some(dir) = dir {
+            layout.root = Some(dir.to_string());
+        }
+        layout.build(session)
+    }
+
+    fn save(&self, name: &str, layout: &SessionLayout) -> Result<()> {
+        let path = self.dir.join(format!("{}.toml", name));
+        if path.exists() {
+            return Err(TmuxError::TemplateExists(path.display().to_string()));
+        }
+        let content = toml::to_string_pretty(layout).map_err(|e| TmuxError::BadTemplate {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        fs::create_dir_all(&self.dir)?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use crate::tmux::{Result, TmuxBackend};
+
+use super::layout::{PaneLayout, SessionLayout, WindowLayout, shorten_home};
+
+/// Programs that are safe to start again with no arguments: editors, pagers
+/// and system monitors. Anything else (`ssh`, `python`, ...) would open a
+/// prompt or a REPL, so it isn't saved.
+pub const RESTARTABLE: &[&str] = &[
+    "vi", "vim", "nvim", "emacs", "nano", "hx", "kak", "micro", "less", "more", "top", "htop",
+    "btop", "tig", "lazygit",
+];
+
+/// Read the windows and panes of a running session into a `SessionLayout`.
+///
+/// Window layouts are kept verbatim, so the template reproduces the exact
+/// splits. Only the program name of each pane is known to tmux, not its
+/// arguments, so only `RESTARTABLE` programs are kept as pane commands.
+pub fn snapshot(backend: &dyn TmuxBackend, session: &str) -> Result<SessionLayout> {
+    let root = backend
+        .list_sessions()?
+        .into_iter()
+        .find(|s| s.name == session)
+        .map(|s| s.path)
+        .filter(|p| !p.is_empty());
+
+    let mut windows = Vec::new();
+    for window in backend.list_windows(session)? {
+        let panes = backend
+            .list_panes(&window.target(session))?
+            .into_iter()
+            .map(|pane| PaneLayout {
+                index: Some(pane.index),
+                root: (Some(&pane.path) != root.as_ref()).then(|| shorten_home(&pane.path)),
+                command: RESTARTABLE
+                    .contains(&pane.command.as_str())
+                    .then_some(pane.command),
+                focus: pane.active,
+                ..Default::default()
+            })
+            .collect();
+
+        windows.push(WindowLayout {
+            index: Some(window.index),
+            name: Some(window.name),
+            layout: Some(window.layout),
+            focus: window.active,
+            panes,
+            ..Default::default()
+        });
+    }
+
+    Ok(SessionLayout {
+        root: root.map(|r| shorten_home(&r)),
+        windows,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tmux::fake::FakeBackend;
+    use crate::tmux::{Pane, Window};
+
+    #[test]
+    fn only_restartable_commands_are_saved() {
+        let pane = |index, command: &str| Pane {
+            index,
+            command: command.to_string(),
+            ..Default::default()
+        };
+        let fake = FakeBackend::new()
+            .with_sessions(&["api"])
+            .with_windows(
+                "api",
+                vec![Window {
+                    index: 0,
+                    ..Default::default()
+                }],
+            )
+            .with_panes(
+                "api:0",
+                vec![
+                    pane(0, "nvim"),
+                    pane(1, "ssh"),
+                    pane(2, "zsh"),
+                    pane(3, "python"),
+                ],
+            );
+
+        let layout = snapshot(&fake, "api").unwrap();
+        let commands: Vec<Option<&str>> = layout.windows[0]
+            .panes
+            .iter()
+            .map(|p| p.command.as_deref())
+            .collect();
+        assert_eq!(commands, [Some("nvim"), None, None, None]);
+    }
+}
@@ -0,0 +1,167 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::tmux::command::{run, run_interactive_in, with_terminal_suspended};
+use crate::tmux::{Result, TmuxError};
+
+use super::TemplateEngine;
+use super::layout::SessionLayout;
+
+/// Session files from `~/.tmuxifier/layouts`.
+pub struct Tmuxifier;
+
+impl Tmuxifier {
+    /// Where tmuxifier looks for `*.session.sh` files.
+    fn layout_dir() -> PathBuf {
+        if let Some(dir) = env::var_os("TMUXIFIER_LAYOUT_PATH") {
+            return PathBuf::from(dir);
+        }
+        let root = env::var_os("TMUXIFIER")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(".tmuxifier"));
+        root.join("layouts")
+    }
+}
+
+impl TemplateEngine for Tmuxifier {
+    fn name(&self) -> &'static str {
+        "tmuxifier"
+    }
+
+    fn list_templates(&self) -> Result<Vec<String>> {
+        let output = run("tmuxifier", &["ls"])?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().map(|s| s.to_string()).collect())
+    }
+
+    fn launch(&self, template: &str, session: &str, dir: Option<&str>) -> Result<()> {
+        // load-session attaches, so it needs the real terminal
+        with_terminal_suspended(|| {
+            run_interactive_in("tmuxifier", &["load-session", template, session], dir)
+        })
+    }
+
+    fn save(&self, name: &str, layout: &SessionLayout) -> Result<()> {
+        let dir = Self::layout_dir();
+        let path = dir.join(format!("{}.session.sh", name));
+        if path.exists() {
+            return Err(TmuxError::TemplateExists(path.display().to_string()));
+        }
+        fs::create_dir_all(&dir)?;
+        fs::write(path, session_script(name, layout))?;
+        Ok(())
+    }
+}
+
+/// Render `layout` as a tmuxifier session file.
+fn session_script(name: &str, layout: &SessionLayout) -> String {
+    let mut lines = vec![
+        format!("# Session \"{}\", saved by tmux-sessioniber.", name),
+        String::new(),
+    ];
+    if let Some(root) = &layout.root {
+        lines.push(format!("session_root {}", quote(root)));
+    }
+    lines.push(String::new());
+    lines.push(format!("if initialize_session {}; then", quote(name)));
+
+    let mut focus_window = None;
+    for (w, window) in layout.windows.iter().enumerate() {
+        lines.push(String::new());
+        let first_root = window
+            .panes
+            .first()
+            .and_then(|p| p.root.as_ref())
+            .or(window.root.as_ref());
+        if let Some(root) = first_root {
+            lines.push(format!("  window_root {}", quote(root)));
+        }
+        lines.push(format!(
+            "  new_window {}",
+            quote(window.name.as_deref().unwrap_or(""))
+        ));
+
+        for (p, pane) in window.panes.iter().enumerate() {
+            if p > 0 {
+                lines.push("  split_h".to_string());
+                if let Some(root) = pane.root.as_ref().filter(|r| Some(*r) != first_root) {
+                    lines.push(format!("  run_cmd {}", quote(&format!("cd {}", root))));
+                }
+            }
+            for command in pane.command.iter().chain(&pane.commands) {
+                lines.push(format!("  run_cmd {}", quote(command)));
+            }
+        }
+        if let Some(tmux_layout) = &window.layout {
+            lines.push(format!(
+                "  tmux select-layout -t \"$session:$window\" {}",
+                quote(tmux_layout)
+            ));
+        }
+        // tmuxifier numbers panes and windows from the base indices, so
+        // target them by the indices they had when saved
+        if let Some((p, pane)) = window.panes.iter().enumerate().find(|(_, p)| p.focus) {
+            lines.push(format!("  select_pane {}", pane.index.unwrap_or(p as u32)));
+        }
+        if window.focus {
+            focus_window = Some(window.index.unwrap_or(w as u32));
+        }
+    }
+
+    if let Some(w) = focus_window {
+        lines.push(String::new());
+        lines.push(format!("  select_window {}", w));
+    }
+    lines.push(String::new());
+    lines.push("fi".to_string());
+    lines.push(String::new());
+    lines.push("finalize_and_go_to_session".to_string());
+    lines.join("\n") + "\n"
+}
+
+/// Single-quote `s` for sh.
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates::snapshot::snapshot;
+    use crate::tmux::fake::FakeBackend;
+    use crate::tmux::{Pane, Window};
+
+    #[test]
+    fn focus_uses_the_saved_indices() {
+        // base-index 1 and pane-base-index 1, second window and pane focused
+        let window = |index, active| Window {
+            index,
+            active,
+            name: format!("w{}", index),
+            ..Default::default()
+        };
+        let pane = |index, active| Pane {
+            index,
+            active,
+            command: "zsh".to_string(),
+            ..Default::default()
+        };
+        let fake = FakeBackend::new()
+            .with_sessions(&["api"])
+            .with_windows("api", vec![window(1, false), window(2, true)])
+            .with_panes("api:1", vec![pane(1, true)])
+            .with_panes("api:2", vec![pane(1, false), pane(2, true)]);
+
+        let script = session_script("api", &snapshot(&fake, "api").unwrap());
+        let selects: Vec<&str> = script
+            .lines()
+            .map(str::trim)
+            .filter(|l| l.starts_with("select_"))
+            .collect();
+        assert_eq!(
+            selects,
+            ["select_pane 1", "select_pane 2", "select_window 2"]
+        );
+    }
+}
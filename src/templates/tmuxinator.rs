@@ -0,0 +1,33 @@
+use crate::tmux::Result;
+use crate::tmux::command::{run, run_in};
+
+use super::TemplateEngine;
+
+/// Projects from `~/.config/tmuxinator`.
+pub struct Tmuxinator;
+
+impl TemplateEngine for Tmuxinator {
+    fn name(&self) -> &'static str {
+        "tmuxinator"
+    }
+
+    fn list_templates(&self) -> Result<Vec<String>> {
+        // `-n` prints one project per line after a "tmuxinator projects:" header
+        let output = run("tmuxinator", &["list", "-n"])?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .lines()
+            .filter(|l| !l.is_empty() && !l.ends_with(':'))
+            .map(|s| s.trim().to_string())
+            .collect())
+    }
+
+    fn launch(&self, template: &str, session: &str, dir: Option<&str>) -> Result<()> {
+        run_in(
+            "tmuxinator",
+            &["start", template, "--name", session, "--no-attach"],
+            dir,
+        )
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,28 @@
+use crate::tmux::Result;
+use crate::tmux::command::{run, run_in};
+
+use super::TemplateEngine;
+
+/// Workspace files from `~/.tmuxp`.
+pub struct Tmuxp;
+
+impl TemplateEngine for Tmuxp {
+    fn name(&self) -> &'static str {
+        "tmuxp"
+    }
+
+    fn list_templates(&self) -> Result<Vec<String>> {
+        let output = run("tmuxp", &["ls"])?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .lines()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect())
+    }
+
+    fn launch(&self, template: &str, session: &str, dir: Option<&str>) -> Result<()> {
+        // -d: don't attach, -y: answer yes to any prompt
+        run_in("tmuxp", &["load", "-d", "-y", "-s", session, template], dir).map(|_| ())
+    }
+}
@@ -14,6 +14,8 @@ pub struct Theme {
     pub border_color: Color,
     pub highlight: ThemeColor,
     pub text_color: Color,
+    /// Characters matched by the fuzzy search.
+    pub match_color: Color,
     pub border_type: BorderType,
 }
 
@@ -28,6 +30,7 @@ impl Theme {
                 fg: parse_hex_color(&tc.highlight.fg),
             },
             text_color: parse_hex_color(&tc.text_color),
+            match_color: parse_hex_color(&tc.match_color),
             border_type: match tc.border_type.as_deref() {
                 Some("Plain") => BorderType::Plain,
                 Some("Double") => BorderType::Double,
@@ -55,26 +58,23 @@ impl Theme {
 
     /// Popup style (thick border + highlight color)
     pub fn popup_block_style<'a>(&self, title: &'a str) -> Block<'a> {
-       let mut block = Block::default()
+        Block::default()
             .title(title)
             // .title_alignment(Alignment::Center)
             .borders(Borders::ALL)
             .border_style(Style::default().fg(self.highlight.fg))
-            .border_type(self.border_type);
-        block
+            .border_type(self.border_type)
     }
 }
 
 /// helper to parse hex like "#RRGGBB"
 fn parse_hex_color(hex: &str) -> Color {
     let hex = hex.trim_start_matches('#');
-    if hex.len() == 6 {
-        if let Ok(rgb) = u32::from_str_radix(hex, 16) {
-            let r = ((rgb >> 16) & 0xFF) as u8;
-            let g = ((rgb >> 8) & 0xFF) as u8;
-            let b = (rgb & 0xFF) as u8;
-            return Color::Rgb(r, g, b);
-        }
+    if hex.len() == 6 && let Ok(rgb) = u32::from_str_radix(hex, 16) {
+        let r = ((rgb >> 16) & 0xFF) as u8;
+        let g = ((rgb >> 8) & 0xFF) as u8;
+        let b = (rgb & 0xFF) as u8;
+        return Color::Rgb(r, g, b);
     }
     Color::Gray
 }
@@ -0,0 +1,108 @@
+use crossterm::{
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use std::io;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::{Result, TmuxError};
+
+/// Run a command to completion, capturing its output and classifying failures.
+pub(crate) fn run(program: &str, args: &[&str]) -> Result<Output> {
+    run_in(program, args, None)
+}
+
+/// Like [`run`], but in the working directory `dir` when given.
+pub(crate) fn run_in(program: &str, args: &[&str], dir: Option<&str>) -> Result<Output> {
+    let output = command(program, args, dir)
+        .output()
+        .map_err(|e| spawn_error(program, e))?;
+
+    if output.status.success() {
+        return Ok(output);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if stderr.starts_with("no server running") || stderr.starts_with("error connecting to") {
+        return Err(TmuxError::NoServer);
+    }
+    Err(TmuxError::CommandFailed {
+        command: command_line(program, args),
+        stderr,
+    })
+}
+
+/// Run tmux for output that is parsed back, such as `-F` formats. `-u`
+/// makes tmux write UTF-8 whatever the locale; without it, a client in a
+/// non-UTF-8 locale prints the tabs separating the fields as `_`.
+pub(crate) fn run_tmux_output(args: &[&str]) -> Result<Output> {
+    let args: Vec<&str> = std::iter::once("-u").chain(args.iter().copied()).collect();
+    run("tmux", &args)
+}
+
+/// Run a command attached to the user's terminal (e.g. `attach-session`).
+pub(crate) fn run_interactive(program: &str, args: &[&str]) -> Result<()> {
+    run_interactive_in(program, args, None)
+}
+
+/// Like [`run_interactive`], but in the working directory `dir` when given.
+pub(crate) fn run_interactive_in(program: &str, args: &[&str], dir: Option<&str>) -> Result<()> {
+    let status = command(program, args, dir)
+        .status()
+        .map_err(|e| spawn_error(program, e))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(TmuxError::CommandFailed {
+            command: command_line(program, args),
+            stderr: format!("exited with {}", status),
+        })
+    }
+}
+
+fn command(program: &str, args: &[&str], dir: Option<&str>) -> Command {
+    let mut command = Command::new(program);
+    command.args(args);
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    command
+}
+
+fn spawn_error(program: &str, e: io::Error) -> TmuxError {
+    if e.kind() == io::ErrorKind::NotFound {
+        TmuxError::BinaryMissing(program.to_string())
+    } else {
+        TmuxError::Io(e)
+    }
+}
+
+fn command_line(program: &str, args: &[&str]) -> String {
+    std::iter::once(program)
+        .chain(args.iter().copied())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether the TUI owns the terminal. Commands run from the CLI leave it alone.
+static TUI_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_tui_active(active: bool) {
+    TUI_ACTIVE.store(active, Ordering::Relaxed);
+}
+
+/// Leave the TUI while `f` runs, then restore it even if `f` failed.
+pub(crate) fn with_terminal_suspended<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    if !TUI_ACTIVE.load(Ordering::Relaxed) {
+        return f();
+    }
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)?;
+
+    let result = f();
+
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    enable_raw_mode()?;
+    result
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+use std::io;
+
+/// Everything that can go wrong while talking to tmux or a template engine.
+#[derive(Debug)]
+pub enum TmuxError {
+    /// The binary (e.g. `tmux`, `tmuxifier`) is not on `$PATH`.
+    BinaryMissing(String),
+    /// tmux is installed but no server is running.
+    NoServer,
+    /// The command ran but exited unsuccessfully.
+    CommandFailed { command: String, stderr: String },
+    /// `Settings.template_engine` names an engine we don't know.
+    UnknownEngine(String),
+    /// A template file could not be parsed.
+    BadTemplate { path: String, message: String },
+    /// A saved server state could not be read.
+    BadStateFile { path: String, message: String },
+    /// A template with that name already exists.
+    TemplateExists(String),
+    /// The operation isn't supported here (e.g. saving templates with tmuxp).
+    Unsupported(String),
+    /// Spawning the command or driving the terminal failed.
+    Io(io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, TmuxError>;
+
+impl fmt::Display for TmuxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TmuxError::BinaryMissing(bin) => {
+                write!(f, "`{}` is not installed or not on $PATH", bin)
+            }
+            TmuxError::NoServer => write!(f, "no tmux server is running"),
+            TmuxError::CommandFailed { command, stderr } => {
+                write!(f, "`{}` failed: {}", command, stderr)
+            }
+            TmuxError::UnknownEngine(name) => write!(f, "unknown template engine `{}`", name),
+            TmuxError::BadTemplate { path, message } => {
+                write!(f, "invalid template {}: {}", path, message)
+            }
+            TmuxError::BadStateFile { path, message } => {
+                write!(f, "invalid state file {}: {}", path, message)
+            }
+            TmuxError::TemplateExists(path) => write!(f, "template {} already exists", path),
+            TmuxError::Unsupported(what) => write!(f, "{}", what),
+            TmuxError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TmuxError {}
+
+impl From<io::Error> for TmuxError {
+    fn from(e: io::Error) -> Self {
+        TmuxError::Io(e)
+    }
+}
@@ -0,0 +1,181 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::templates::Template;
+use crate::templates::layout::SessionLayout;
+
+use super::{Pane, Result, Session, TmuxBackend, TmuxError, Window};
+
+/// A call made against the fake backend, in the order it happened.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Call {
+    Create {
+        name: String,
+        template: Option<String>,
+        dir: Option<String>,
+    },
+    BuildLayout(String),
+    SaveTemplate(String),
+    Delete(String),
+    Rename {
+        old: String,
+        new: String,
+    },
+    Select(String),
+    Attach(String),
+}
+
+/// In-memory backend that keeps a list of sessions and records every call.
+#[derive(Default)]
+pub struct FakeBackend {
+    pub sessions: Vec<Session>,
+    /// Windows keyed by session name.
+    pub windows: HashMap<String, Vec<Window>>,
+    /// Panes keyed by `session:window`.
+    pub panes: HashMap<String, Vec<Pane>>,
+    /// Captured pane contents keyed by target.
+    pub captures: HashMap<String, String>,
+    pub templates: Vec<Template>,
+    /// Shared with the test, which keeps a handle from `calls()` after
+    /// boxing the backend into `App`.
+    pub calls: Rc<RefCell<Vec<Call>>>,
+    /// Returned (once) by the next mutating call instead of succeeding.
+    pub fail_next: Option<TmuxError>,
+    /// Pretend the picker runs inside tmux, so attaching switches the client.
+    pub inside_tmux: bool,
+}
+
+impl FakeBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_sessions(mut self, sessions: &[&str]) -> Self {
+        self.sessions = sessions.iter().map(|s| Session::new(s)).collect();
+        self
+    }
+
+    pub fn with_templates(mut self, templates: &[&str]) -> Self {
+        self.templates = templates.iter().map(|s| Template::new("fake", s)).collect();
+        self
+    }
+
+    pub fn with_windows(mut self, session: &str, windows: Vec<Window>) -> Self {
+        self.windows.insert(session.to_string(), windows);
+        self
+    }
+
+    pub fn with_panes(mut self, target: &str, panes: Vec<Pane>) -> Self {
+        self.panes.insert(target.to_string(), panes);
+        self
+    }
+
+    /// Handle to the recorded calls that outlives moving the backend.
+    pub fn calls(&self) -> Rc<RefCell<Vec<Call>>> {
+        Rc::clone(&self.calls)
+    }
+
+    fn check_failure(&mut self) -> Result<()> {
+        match self.fail_next.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+impl TmuxBackend for FakeBackend {
+    fn list_sessions(&self) -> Result<Vec<Session>> {
+        Ok(self.sessions.clone())
+    }
+
+    fn list_windows(&self, session: &str) -> Result<Vec<Window>> {
+        Ok(self.windows.get(session).cloned().unwrap_or_default())
+    }
+
+    fn list_panes(&self, target: &str) -> Result<Vec<Pane>> {
+        Ok(self.panes.get(target).cloned().unwrap_or_default())
+    }
+
+    fn capture_pane(&self, target: &str) -> Result<String> {
+        Ok(self.captures.get(target).cloned().unwrap_or_default())
+    }
+
+    fn list_templates(&self) -> Result<Vec<Template>> {
+        Ok(self.templates.clone())
+    }
+
+    fn create_session(
+        &mut self,
+        name: &str,
+        template: Option<&Template>,
+        dir: Option<&str>,
+    ) -> Result<()> {
+        self.check_failure()?;
+        self.calls.borrow_mut().push(Call::Create {
+            name: name.to_string(),
+            template: template.map(|t| t.name.clone()),
+            dir: dir.map(|d| d.to_string()),
+        });
+        let mut session = Session::new(name);
+        session.path = dir.unwrap_or_default().to_string();
+        self.sessions.push(session);
+        Ok(())
+    }
+
+    fn build_layout(&mut self, name: &str, _layout: &SessionLayout) -> Result<()> {
+        self.check_failure()?;
+        self.calls
+            .borrow_mut()
+            .push(Call::BuildLayout(name.to_string()));
+        self.sessions.push(Session::new(name));
+        Ok(())
+    }
+
+    fn save_template(&mut self, name: &str, _layout: &SessionLayout) -> Result<Template> {
+        self.check_failure()?;
+        self.calls
+            .borrow_mut()
+            .push(Call::SaveTemplate(name.to_string()));
+        let template = Template::new("fake", name);
+        self.templates.push(template.clone());
+        Ok(template)
+    }
+
+    fn delete_session(&mut self, name: &str) -> Result<()> {
+        self.check_failure()?;
+        self.calls.borrow_mut().push(Call::Delete(name.to_string()));
+        self.sessions.retain(|s| s.name != name);
+        Ok(())
+    }
+
+    fn rename_session(&mut self, old: &str, new: &str) -> Result<()> {
+        self.check_failure()?;
+        self.calls.borrow_mut().push(Call::Rename {
+            old: old.to_string(),
+            new: new.to_string(),
+        });
+        if let Some(s) = self.sessions.iter_mut().find(|s| s.name == old) {
+            s.name = new.to_string();
+        }
+        Ok(())
+    }
+
+    fn select_target(&mut self, target: &str) -> Result<()> {
+        self.check_failure()?;
+        self.calls
+            .borrow_mut()
+            .push(Call::Select(target.to_string()));
+        Ok(())
+    }
+
+    fn attach_session(&mut self, name: &str) -> Result<()> {
+        self.check_failure()?;
+        self.calls.borrow_mut().push(Call::Attach(name.to_string()));
+        Ok(())
+    }
+
+    fn switches_client(&self) -> bool {
+        self.inside_tmux
+    }
+}
@@ -0,0 +1,51 @@
+pub mod command;
+pub mod error;
+pub mod process;
+pub mod session;
+pub mod window;
+// In-memory backend for driving `App` and `handle_input` without a tmux server.
+#[cfg(test)]
+pub mod fake;
+
+pub use error::{Result, TmuxError};
+pub use process::ProcessBackend;
+pub use session::Session;
+pub use window::{Pane, Window};
+
+use crate::templates::Template;
+use crate::templates::layout::SessionLayout;
+
+/// Every tmux (and template engine) operation the app performs.
+///
+/// `App` only talks to tmux through this trait, so the real process-based
+/// backend can be swapped for `fake::FakeBackend` when testing input flows.
+pub trait TmuxBackend {
+    /// Running sessions; an empty list when no server is running.
+    fn list_sessions(&self) -> Result<Vec<Session>>;
+    fn list_windows(&self, session: &str) -> Result<Vec<Window>>;
+    /// Panes of a window, `target` being `session:window`.
+    fn list_panes(&self, target: &str) -> Result<Vec<Pane>>;
+    /// Visible contents of the pane `target`, with ANSI escapes kept.
+    fn capture_pane(&self, target: &str) -> Result<String>;
+    /// Templates from every configured template engine.
+    fn list_templates(&self) -> Result<Vec<Template>>;
+    /// Create a detached session, from `template` if given, starting in `dir`.
+    fn create_session(
+        &mut self,
+        name: &str,
+        template: Option<&Template>,
+        dir: Option<&str>,
+    ) -> Result<()>;
+    /// Create the detached session `name` from a native layout.
+    fn build_layout(&mut self, name: &str, layout: &SessionLayout) -> Result<()>;
+    /// Store `layout` as template `name` with the first engine that can save templates.
+    fn save_template(&mut self, name: &str, layout: &SessionLayout) -> Result<Template>;
+    fn delete_session(&mut self, name: &str) -> Result<()>;
+    fn rename_session(&mut self, old: &str, new: &str) -> Result<()>;
+    /// Make the window or pane `target` the active one of its session.
+    fn select_target(&mut self, target: &str) -> Result<()>;
+    /// Attach to `name`, or switch the current client to it when running inside tmux.
+    fn attach_session(&mut self, name: &str) -> Result<()>;
+    /// Whether `attach_session` switches an existing client instead of attaching.
+    fn switches_client(&self) -> bool;
+}
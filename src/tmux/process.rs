@@ -0,0 +1,238 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::templates::layout::SessionLayout;
+use crate::templates::{self, Template, TemplateEngine};
+
+use super::command::{run, run_interactive, run_tmux_output, with_terminal_suspended};
+use super::{Pane, Result, Session, TmuxBackend, TmuxError, Window};
+
+/// Backend that shells out to the `tmux` binary and the configured template engines.
+#[derive(Default)]
+pub struct ProcessBackend {
+    /// Set when the picker itself runs inside a tmux client (`$TMUX` is set).
+    inside_tmux: bool,
+    /// `Settings.template_engine`, e.g. `native,tmuxp`.
+    engine_setting: String,
+    layouts_dir: PathBuf,
+}
+
+impl ProcessBackend {
+    pub fn new(engine_setting: &str, layouts_dir: &Path) -> Self {
+        ProcessBackend {
+            inside_tmux: env::var_os("TMUX").is_some_and(|v| !v.is_empty()),
+            engine_setting: engine_setting.to_string(),
+            layouts_dir: layouts_dir.to_path_buf(),
+        }
+    }
+
+    /// The configured engines. They are built on use, so an unknown engine
+    /// name only breaks what needs templates.
+    fn engines(&self) -> Result<Vec<Box<dyn TemplateEngine>>> {
+        templates::from_setting(&self.engine_setting, &self.layouts_dir)
+    }
+}
+
+/// The templates of every engine in `engines`.
+fn list_all(engines: &[Box<dyn TemplateEngine>]) -> Result<Vec<Template>> {
+    let mut templates = Vec::new();
+    let mut first_error = None;
+    for engine in engines {
+        match engine.list_templates() {
+            Ok(names) => templates.extend(names.iter().map(|n| Template::new(engine.name(), n))),
+            // an optional tool that isn't installed only matters when it's the only engine
+            Err(TmuxError::BinaryMissing(_)) if engines.len() > 1 => {}
+            Err(e) => first_error = first_error.or(Some(e)),
+        }
+    }
+    // with several engines, one broken engine shouldn't hide the others
+    match first_error {
+        Some(e) if templates.is_empty() => Err(e),
+        _ => Ok(templates),
+    }
+}
+
+impl TmuxBackend for ProcessBackend {
+    fn list_sessions(&self) -> Result<Vec<Session>> {
+        match run_tmux_output(&["list-sessions", "-F", Session::FORMAT]) {
+            Ok(output) => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                Ok(stdout.lines().filter_map(Session::parse).collect())
+            }
+            Err(TmuxError::NoServer) => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn list_windows(&self, session: &str) -> Result<Vec<Window>> {
+        let output = run_tmux_output(&["list-windows", "-t", session, "-F", Window::FORMAT])?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().filter_map(Window::parse).collect())
+    }
+
+    fn list_panes(&self, target: &str) -> Result<Vec<Pane>> {
+        let output = run_tmux_output(&["list-panes", "-t", target, "-F", Pane::FORMAT])?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().filter_map(Pane::parse).collect())
+    }
+
+    fn capture_pane(&self, target: &str) -> Result<String> {
+        let output = run("tmux", &["capture-pane", "-p", "-e", "-t", target])?;
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn list_templates(&self) -> Result<Vec<Template>> {
+        list_all(&self.engines()?)
+    }
+
+    fn create_session(
+        &mut self,
+        name: &str,
+        template: Option<&Template>,
+        dir: Option<&str>,
+    ) -> Result<()> {
+        match template {
+            Some(t) => {
+                let engine = self
+                    .engines()?
+                    .into_iter()
+                    .find(|e| e.name() == t.engine)
+                    .ok_or_else(|| TmuxError::UnknownEngine(t.engine.clone()))?;
+                engine.launch(&t.name, name, dir)
+            }
+            None => {
+                let mut args = vec!["new-session", "-d", "-s", name];
+                if let Some(dir) = dir {
+                    args.extend(["-c", dir]);
+                }
+                run("tmux", &args).map(|_| ())
+            }
+        }
+    }
+
+    fn build_layout(&mut self, name: &str, layout: &SessionLayout) -> Result<()> {
+        layout.build(name)
+    }
+
+    fn save_template(&mut self, name: &str, layout: &SessionLayout) -> Result<Template> {
+        for engine in self.engines()? {
+            match engine.save(name, layout) {
+                Ok(()) => return Ok(Template::new(engine.name(), name)),
+                Err(TmuxError::Unsupported(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Err(TmuxError::Unsupported(
+            "no configured template engine can save templates".to_string(),
+        ))
+    }
+
+    fn delete_session(&mut self, name: &str) -> Result<()> {
+        run("tmux", &["kill-session", "-t", name]).map(|_| ())
+    }
+
+    fn rename_session(&mut self, old: &str, new: &str) -> Result<()> {
+        run("tmux", &["rename-session", "-t", old, new]).map(|_| ())
+    }
+
+    fn select_target(&mut self, target: &str) -> Result<()> {
+        run("tmux", &["select-window", "-t", target])?;
+        if target.contains('.') {
+            run("tmux", &["select-pane", "-t", target])?;
+        }
+        Ok(())
+    }
+
+    fn attach_session(&mut self, name: &str) -> Result<()> {
+        if self.inside_tmux {
+            // attaching from inside tmux would nest clients
+            return run("tmux", &["switch-client", "-t", name]).map(|_| ());
+        }
+        with_terminal_suspended(|| run_interactive("tmux", &["attach-session", "-t", name]))
+    }
+
+    fn switches_client(&self) -> bool {
+        self.inside_tmux
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Engine whose template list is fixed.
+    struct StubEngine(fn() -> Result<Vec<String>>);
+
+    impl TemplateEngine for StubEngine {
+        fn name(&self) -> &'static str {
+            "stub"
+        }
+
+        fn list_templates(&self) -> Result<Vec<String>> {
+            (self.0)()
+        }
+
+        fn launch(&self, _template: &str, _session: &str, _dir: Option<&str>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn list(engines: Vec<fn() -> Result<Vec<String>>>) -> Result<Vec<Template>> {
+        list_all(
+            &engines
+                .into_iter()
+                .map(|e| Box::new(StubEngine(e)) as Box<dyn TemplateEngine>)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn missing() -> Result<Vec<String>> {
+        Err(TmuxError::BinaryMissing("tmuxifier".to_string()))
+    }
+
+    #[test]
+    fn missing_engine_next_to_others_is_ignored() {
+        assert!(list(vec![|| Ok(vec![]), missing]).unwrap().is_empty());
+        let templates = list(vec![missing, || Ok(vec!["web".to_string()])]).unwrap();
+        assert_eq!(templates, [Template::new("stub", "web")]);
+    }
+
+    #[test]
+    fn missing_engine_on_its_own_is_reported() {
+        assert!(matches!(
+            list(vec![missing]),
+            Err(TmuxError::BinaryMissing(_))
+        ));
+    }
+
+    #[test]
+    fn broken_engine_is_reported_when_nothing_else_is_listed() {
+        let broken = || {
+            Err(TmuxError::BadTemplate {
+                path: "web.toml".to_string(),
+                message: "bad".to_string(),
+            })
+        };
+        assert!(matches!(
+            list(vec![|| Ok(vec![]), broken]),
+            Err(TmuxError::BadTemplate { .. })
+        ));
+    }
+
+    #[test]
+    fn unknown_engine_only_breaks_templates() {
+        let backend = ProcessBackend::new("native,bogus", Path::new("/nonexistent"));
+        assert!(
+            matches!(backend.list_templates(), Err(TmuxError::UnknownEngine(name)) if name == "bogus")
+        );
+        let template = Template::new("native", "web");
+        assert!(matches!(
+            ProcessBackend::new("bogus", Path::new("/nonexistent")).create_session(
+                "web",
+                Some(&template),
+                None
+            ),
+            Err(TmuxError::UnknownEngine(_))
+        ));
+    }
+}
@@ -0,0 +1,108 @@
+/// A tmux session as reported by `list-sessions -F`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Session {
+    pub name: String,
+    pub windows: usize,
+    /// Number of clients attached to the session.
+    pub attached: usize,
+    /// Unix timestamp of when the session was created.
+    pub created: u64,
+    /// Unix timestamp of the last activity in the session.
+    pub activity: u64,
+    pub group: Option<String>,
+    pub path: String,
+}
+
+impl Session {
+    /// Format string passed to `tmux list-sessions -F`, one field per tab.
+    /// The path goes last so it may contain anything but a newline.
+    pub const FORMAT: &'static str = "#{session_name}\t#{session_windows}\t#{session_attached}\t#{session_created}\t#{session_activity}\t#{session_group}\t#{session_path}";
+
+    /// A one-window session with nothing else known, for the fake backend.
+    #[cfg(test)]
+    pub fn new(name: &str) -> Self {
+        Session {
+            name: name.to_string(),
+            windows: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Parse one line of `list-sessions -F Session::FORMAT` output, or `None`
+    /// when it doesn't have every field.
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(7, '\t');
+        let name = fields.next().filter(|n| !n.is_empty())?.to_string();
+        let mut number = || fields.next()?.parse::<u64>().ok();
+        let windows = number()? as usize;
+        let attached = number()? as usize;
+        let created = number()?;
+        let activity = number()?;
+        let group = fields.next()?;
+        let group = (!group.is_empty()).then(|| group.to_string());
+        let path = fields.next()?.to_string();
+
+        Some(Session {
+            name,
+            windows,
+            attached,
+            created,
+            activity,
+            group,
+            path,
+        })
+    }
+
+    pub fn is_attached(&self) -> bool {
+        self.attached > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_field() {
+        let session =
+            Session::parse("dev\t3\t1\t1700000000\t1700000100\tgroup\t/home/u/dev").unwrap();
+        assert_eq!(
+            session,
+            Session {
+                name: "dev".to_string(),
+                windows: 3,
+                attached: 1,
+                created: 1700000000,
+                activity: 1700000100,
+                group: Some("group".to_string()),
+                path: "/home/u/dev".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn empty_group_is_none() {
+        let session = Session::parse("dev\t1\t0\t1\t2\t\t/").unwrap();
+        assert_eq!(session.group, None);
+        assert!(!session.is_attached());
+    }
+
+    #[test]
+    fn path_keeps_its_tabs() {
+        let session = Session::parse("dev\t1\t0\t1\t2\t\t/tmp/a\tb").unwrap();
+        assert_eq!(session.path, "/tmp/a\tb");
+    }
+
+    #[test]
+    fn malformed_lines_are_rejected() {
+        // tabs printed as `_` by a tmux client in a non-UTF-8 locale
+        assert_eq!(
+            Session::parse("dev1_1_0_1792319974_1792319974__/root"),
+            None
+        );
+        assert_eq!(Session::parse("\t1\t0\t1\t2\t\t/"), None);
+        assert_eq!(Session::parse("dev\tmany\t0\t1\t2\t\t/"), None);
+        assert_eq!(Session::parse("dev\t1\t0"), None);
+        assert_eq!(Session::parse(""), None);
+    }
+}
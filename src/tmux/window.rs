@@ -0,0 +1,133 @@
+/// A window of a tmux session as reported by `list-windows -F`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Window {
+    pub index: u32,
+    pub name: String,
+    pub active: bool,
+    pub panes: usize,
+    /// Command running in the window's active pane.
+    pub command: String,
+    /// tmux layout string, e.g. `b25d,158x40,0,0{79x40,0,0,1,78x40,80,0,2}`.
+    pub layout: String,
+}
+
+/// A pane of a tmux window as reported by `list-panes -F`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pane {
+    pub index: u32,
+    pub title: String,
+    pub active: bool,
+    pub command: String,
+    pub path: String,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Window {
+    /// Format string passed to `tmux list-windows -F`; the name goes last.
+    pub const FORMAT: &'static str = "#{window_index}\t#{window_active}\t#{window_panes}\t#{window_layout}\t#{pane_current_command}\t#{window_name}";
+
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(6, '\t');
+        let index = fields.next()?.parse().ok()?;
+        let active = fields.next()? == "1";
+        let panes = fields.next()?.parse().unwrap_or(0);
+        let layout = fields.next()?.to_string();
+        let command = fields.next()?.to_string();
+        let name = fields.next().unwrap_or("").to_string();
+
+        Some(Window {
+            index,
+            name,
+            active,
+            panes,
+            command,
+            layout,
+        })
+    }
+
+    /// `session:index`, the tmux target for this window.
+    pub fn target(&self, session: &str) -> String {
+        format!("{}:{}", session, self.index)
+    }
+}
+
+impl Pane {
+    /// Format string passed to `tmux list-panes -F`; the title goes last.
+    pub const FORMAT: &'static str = "#{pane_index}\t#{pane_active}\t#{pane_width}\t#{pane_height}\t#{pane_current_command}\t#{pane_current_path}\t#{pane_title}";
+
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(7, '\t');
+        let index = fields.next()?.parse().ok()?;
+        let active = fields.next()? == "1";
+        let width = fields.next()?.parse().unwrap_or(0);
+        let height = fields.next()?.parse().unwrap_or(0);
+        let command = fields.next()?.to_string();
+        let path = fields.next()?.to_string();
+        let title = fields.next().unwrap_or("").to_string();
+
+        Some(Pane {
+            index,
+            title,
+            active,
+            command,
+            path,
+            width,
+            height,
+        })
+    }
+
+    /// `session:window.index`, the tmux target for this pane.
+    pub fn target(&self, session: &str, window: u32) -> String {
+        format!("{}:{}.{}", session, window, self.index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_window() {
+        let window = Window::parse("2\t1\t3\tb25d,158x40,0,0\tnvim\tedit\tor not").unwrap();
+        assert_eq!(
+            window,
+            Window {
+                index: 2,
+                name: "edit\tor not".to_string(),
+                active: true,
+                panes: 3,
+                command: "nvim".to_string(),
+                layout: "b25d,158x40,0,0".to_string(),
+            }
+        );
+        assert_eq!(window.target("dev"), "dev:2");
+    }
+
+    #[test]
+    fn parses_a_pane() {
+        let pane = Pane::parse("1\t0\t80\t24\tzsh\t/home/u\t").unwrap();
+        assert_eq!(
+            pane,
+            Pane {
+                index: 1,
+                title: String::new(),
+                active: false,
+                command: "zsh".to_string(),
+                path: "/home/u".to_string(),
+                width: 80,
+                height: 24,
+            }
+        );
+        assert_eq!(pane.target("dev", 2), "dev:2.1");
+    }
+
+    #[test]
+    fn malformed_lines_are_rejected() {
+        // tabs printed as `_` by a tmux client in a non-UTF-8 locale
+        assert_eq!(Window::parse("1_1_1_b25d,80x24,0,0_zsh_main"), None);
+        assert_eq!(Window::parse("1\t1\t1"), None);
+        assert_eq!(Pane::parse("0_1_80_24_zsh_/root_host"), None);
+        assert_eq!(Pane::parse("x\t1\t80\t24\tzsh\t/\t"), None);
+    }
+}